@@ -0,0 +1,31 @@
+// Measures how long one simulation tick takes over a long Nightmare run.
+// Run with `cargo bench`. Each line reports one simulated minute; with the bug cap,
+// despawning and the spatial grid the time per tick should stay flat instead of growing
+// with the length of the run.
+use std::hint::black_box;
+use std::time::Instant;
+
+use gaemthesecond::config::{Config, Difficulty};
+use gaemthesecond::input::InputState;
+use gaemthesecond::world::{TICK, World};
+
+// How many simulated minutes to run.
+const MINUTES: u32 = 10;
+// Ticks in one simulated minute.
+const TICKS_PER_MINUTE: u32 = 60 * 60;
+
+fn main() {
+    let mut world = World::new("Bench".to_string(), "blob", 1, Difficulty::Nightmare, Config::default());
+    // Hold forward and turn, so the pet runs in circles and bugs keep chasing it.
+    let input = InputState { forward: true, right: true, ..InputState::default() };
+
+    println!("minute  bugs  us/tick");
+    for minute in 1..=MINUTES {
+        let start = Instant::now();
+        for _ in 0..TICKS_PER_MINUTE {
+            world.step(TICK, black_box(&input));
+        }
+        let per_tick = start.elapsed().as_secs_f64() * 1e6 / TICKS_PER_MINUTE as f64;
+        println!("{:>6}  {:>4}  {:>7.2}", minute, world.bugs.len(), per_tick);
+    }
+}
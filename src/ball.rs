@@ -0,0 +1,189 @@
+use macroquad::math::{Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::terrain::{self, Collider};
+
+// The radius of the ball.
+pub const BALL_RADIUS: f32 = 0.5;
+// Fraction of its speed the ball keeps when it bounces off an obstacle.
+const BOUNCINESS: f32 = 0.7;
+// Downward acceleration of the ball in the air, in units per 60Hz frame per frame.
+pub const GRAVITY: f32 = 0.015;
+// Fraction of its falling speed the ball bounces back up with when it lands.
+const RESTITUTION: f32 = 0.6;
+// Landing slower than this, the ball stops bouncing and rolls on.
+const SETTLE_SPEED: f32 = 0.05;
+// Fraction of its sideways speed the ball keeps when it lands.
+const LANDING_GRIP: f32 = 0.9;
+// Fraction of velocity the ball keeps every 60Hz frame in the air.
+const AIR_DRAG: f32 = 0.995;
+// Speed a rolling ball loses every 60Hz frame on top of friction, so it comes to rest on gentle
+// slopes instead of creeping downhill forever.
+const ROLLING_RESISTANCE: f32 = 0.0005;
+// How much of gravity pulls a ball rolling down a slope. A rolling ball also has to spin up,
+// which takes 2/7 of it.
+const SLOPE_PULL: f32 = GRAVITY * 5.0 / 7.0;
+
+// The Ball struct represents an interactive physics object in the world.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Ball {
+    // Current X-coordinate of the ball.
+    pub x: f32,
+    // Current Z-coordinate of the ball.
+    pub z: f32,
+    // Height of the ground below the ball.
+    #[serde(default)]
+    pub y: f32,
+    // How far the bottom of the ball is above the ground, and how fast that changes.
+    #[serde(default)]
+    pub height: f32,
+    #[serde(default)]
+    pub vy: f32,
+    // Current velocity of the ball on the X-axis, in units per 60Hz frame.
+    pub vx: f32,
+    // Current velocity of the ball on the Z-axis, in units per 60Hz frame.
+    pub vz: f32,
+    // Which way the ball is turned, as a quaternion (x, y, z, w), to show it rolling and spinning.
+    #[serde(default = "no_rotation")]
+    pub orientation: [f32; 4],
+    // How fast the ball spins, as an axis scaled by radians per 60Hz frame. On the ground it
+    // follows from rolling; in the air it keeps whatever spin it left the ground with.
+    #[serde(default)]
+    pub spin: [f32; 3],
+    // The pet is carrying the ball in its mouth. A held ball has no physics of its own.
+    #[serde(default)]
+    pub held: bool,
+}
+
+// Balls saved before they could spin start out unturned.
+fn no_rotation() -> [f32; 4] {
+    Quat::IDENTITY.to_array()
+}
+
+// A default ball is simply a new one at its starting spot.
+impl Default for Ball {
+    fn default() -> Ball {
+        Ball::new()
+    }
+}
+
+// Implement methods for the Ball struct.
+impl Ball {
+    // Constructor to create a new Ball with default starting values.
+    pub fn new() -> Ball {
+        Ball {
+            // Initial position offset from the world origin.
+            x: 5.0,
+            z: 5.0,
+            y: 0.0,
+            // Starts resting on the ground with no initial velocity.
+            height: 0.0,
+            vy: 0.0,
+            vx: 0.0,
+            vz: 0.0,
+            // Starts with zero rotation.
+            orientation: no_rotation(),
+            spin: [0.0; 3],
+            held: false,
+        }
+    }
+
+    // Update function to be called every tick to process ball physics.
+    // friction is the fraction of velocity kept every 60Hz frame while rolling, wind the velocity
+    // the wind adds to the ball every 60Hz frame, and slope how steeply the ground rises along
+    // x and z, which pulls a rolling ball downhill.
+    pub fn update(&mut self, dt: f32, friction: f32, wind: Vec2, slope: Vec2) {
+        // The physics constants were tuned for 60 frames per second, so scale them by the tick length.
+        let frames = dt * 60.0;
+        let on_ground = self.on_ground();
+
+        // The wind pushes the ball along, and slopes roll it downhill.
+        self.vx += wind.x * frames;
+        self.vz += wind.y * frames;
+        if on_ground {
+            self.vx -= slope.x * SLOPE_PULL * frames;
+            self.vz -= slope.y * SLOPE_PULL * frames;
+        }
+
+        // Apply friction to the velocities. Rolling on the ground slows the ball down much more
+        // than flying through the air. With the default friction of 0.95 a rolling ball loses 5% of
+        // its speed every 60Hz frame.
+        let friction = if on_ground { friction } else { AIR_DRAG }.powf(frames);
+        self.vx *= friction;
+        self.vz *= friction;
+        let speed = self.speed();
+        if on_ground && speed > 0.0 {
+            let resisted = (speed - ROLLING_RESISTANCE * frames).max(0.0) / speed;
+            self.vx *= resisted;
+            self.vz *= resisted;
+        }
+
+        // Update the position of the ball based on its current velocity.
+        // This is a basic Euler integration: Position += Velocity * time.
+        self.x += self.vx * frames;
+        self.z += self.vz * frames;
+
+        // In the air the ball falls, and when it lands it bounces back up, a little lower every
+        // time, until it settles down and rolls.
+        if !on_ground {
+            self.vy -= GRAVITY * frames;
+            self.height += self.vy * frames;
+            if self.height <= 0.0 {
+                self.height = 0.0;
+                if self.vy < -SETTLE_SPEED {
+                    self.vy = -self.vy * RESTITUTION;
+                    self.vx *= LANDING_GRIP;
+                    self.vz *= LANDING_GRIP;
+                } else {
+                    self.vy = 0.0;
+                }
+            }
+        }
+
+        // A ball on the ground rolls without slipping: it turns around the axis lying flat and
+        // square to its velocity, by its travelled distance over its radius.
+        if self.on_ground() {
+            self.spin = self.rolling_spin();
+        }
+        let turn = Quat::from_scaled_axis(Vec3::from(self.spin) * frames);
+        self.orientation = (turn * Quat::from_array(self.orientation)).normalize().to_array();
+    }
+
+    // The spin of the ball rolling along the ground at its current velocity.
+    pub fn rolling_spin(&self) -> [f32; 3] {
+        (Vec3::Y.cross(Vec3::new(self.vx, 0.0, self.vz)) / BALL_RADIUS).to_array()
+    }
+
+    // true while the ball is resting or rolling on the ground, not flying or bouncing.
+    pub fn on_ground(&self) -> bool {
+        self.height <= 0.0 && self.vy <= 0.0
+    }
+
+    // The horizontal speed of the ball, in units per 60Hz frame.
+    pub fn speed(&self) -> f32 {
+        Vec2::new(self.vx, self.vz).length()
+    }
+
+    // Bounce the ball off the obstacles it rolled into: it is pushed back out, and the part of
+    // its velocity going into the obstacle is reflected and slightly damped.
+    pub fn bounce(&mut self, obstacles: &[Collider]) {
+        let mut pos = Vec2::new(self.x, self.z);
+        if let Some(normal) = terrain::push_out(&mut pos, BALL_RADIUS, obstacles) {
+            self.x = pos.x;
+            self.z = pos.y;
+            self.deflect(normal);
+        }
+    }
+
+    // Bounce off something in the direction of the given unit normal: the part of the velocity
+    // going into it is reflected, and the ball slows down a little.
+    pub fn deflect(&mut self, normal: Vec2) {
+        let velocity = Vec2::new(self.vx, self.vz);
+        let into = velocity.dot(normal);
+        if into < 0.0 {
+            let bounced = (velocity - normal * into * 2.0) * BOUNCINESS;
+            self.vx = bounced.x;
+            self.vz = bounced.y;
+        }
+    }
+}
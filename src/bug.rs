@@ -0,0 +1,292 @@
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::spatial::SpatialGrid;
+use crate::terrain::{self, Collider};
+
+// The different species of bug. Each one moves in its own way and is drawn with its own model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BugKind {
+    // Small bugs that chase the pet in flocks, keeping apart from and close to each other.
+    #[default]
+    Swarmer,
+    // Fast bugs that circle around the pet for a while before charging straight at it.
+    Flanker,
+    // Slow, armoured beetles that take several hits to squash.
+    Beetle,
+    // Bugs that hop through the air towards the pet, over anything in their way.
+    Flyer,
+}
+
+impl BugKind {
+    // Every kind of bug, in the order used by the spawn weights.
+    pub const ALL: [BugKind; 4] = [BugKind::Swarmer, BugKind::Flanker, BugKind::Beetle, BugKind::Flyer];
+
+    // How fast this kind moves compared to the current global bug speed.
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            BugKind::Swarmer => 1.0,
+            BugKind::Flanker => 1.2,
+            BugKind::Beetle => 0.5,
+            BugKind::Flyer => 0.9,
+        }
+    }
+
+    // How many hits it takes to squash this kind.
+    pub fn max_health(self) -> u32 {
+        match self {
+            BugKind::Beetle => 3,
+            _ => 1,
+        }
+    }
+
+    // The size of the bug's body, used for drawing and for hits by the ball.
+    pub fn radius(self) -> f32 {
+        match self {
+            BugKind::Swarmer | BugKind::Flanker => 0.3,
+            BugKind::Beetle => 0.5,
+            BugKind::Flyer => 0.25,
+        }
+    }
+}
+
+// How close a flanker circles around the pet before it charges.
+const ORBIT_RADIUS: f32 = 8.0;
+// Seconds a flanker circles before charging.
+const ORBIT_TIME: f32 = 3.0;
+// How much faster a flanker moves while charging.
+const CHARGE_FACTOR: f32 = 1.6;
+// How high a flyer hops, and how many hops it makes per second (in radians of the hop wave).
+const HOP_HEIGHT: f32 = 1.2;
+const HOP_RATE: f32 = 4.0;
+// Seconds after a hit during which a bug cannot be hit again.
+const HIT_COOLDOWN: f32 = 0.5;
+// Fraction of its knockback a bug keeps every 60Hz frame.
+const KNOCKBACK_GRIP: f32 = 0.85;
+// Swarmers within this distance of each other count as one flock.
+const FLOCK_RADIUS: f32 = 3.0;
+// Swarmers closer than this push away from each other.
+const SEPARATION_RADIUS: f32 = 1.0;
+// How far ahead a walking bug looks for obstacles to steer around.
+pub const LOOKAHEAD: f32 = 3.0;
+// Room a bug tries to keep between itself and an obstacle when going around it.
+const AVOID_MARGIN: f32 = 0.3;
+// Only this many nearby bugs are looked at, swarmers or not, so a huge swarm around the pet stays cheap.
+const MAX_NEIGHBOURS: usize = 12;
+
+// The Bug struct represents an enemy that chases the pet.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bug {
+    // Current X-coordinate of the bug.
+    pub x: f32,
+    // Current Z-coordinate of the bug.
+    pub z: f32,
+    // Height above the ground. Only flyers leave the ground.
+    #[serde(default)]
+    pub y: f32,
+    // Movement speed of the bug, in units per 60Hz frame.
+    pub speed: f32,
+    // Which species this bug is.
+    #[serde(default)]
+    pub kind: BugKind,
+    // Hits left before the bug is squashed.
+    #[serde(default = "default_health")]
+    pub health: u32,
+    // The direction the bug last moved in (yaw, like the pet), used to point its face.
+    #[serde(default)]
+    pub heading: f32,
+    // Seconds spent circling the pet (flankers) or the hop wave position (flyers).
+    #[serde(default)]
+    pub timer: f32,
+    // +1 or -1: which way a flanker circles.
+    #[serde(default = "default_orbit_dir")]
+    pub orbit_dir: f32,
+    // Seconds until the bug can be hit again.
+    #[serde(default)]
+    pub hit_cooldown: f32,
+    // The latest flocking steering of a swarmer. It is refreshed every few ticks, and saved
+    // so that a continued or replayed run keeps steering the same way.
+    #[serde(default)]
+    pub flock: (f32, f32),
+    // Velocity the bug was knocked away with by the ball, in units per 60Hz frame. It wears off quickly.
+    #[serde(default)]
+    pub knockback: (f32, f32),
+}
+
+// Bugs saved before bug kinds existed were all simple one-hit bugs.
+fn default_health() -> u32 {
+    1
+}
+
+fn default_orbit_dir() -> f32 {
+    1.0
+}
+
+impl Bug {
+    // Constructor to create a new Bug of the given kind at a specific position.
+    // orbit_dir only matters for flankers and picks which way they circle.
+    pub fn new(x: f32, z: f32, speed: f32, kind: BugKind, orbit_dir: f32) -> Bug {
+        Bug {
+            x,
+            z,
+            y: 0.0,
+            speed,
+            kind,
+            health: kind.max_health(),
+            heading: 0.0,
+            timer: 0.0,
+            orbit_dir,
+            hit_cooldown: 0.0,
+            flock: (0.0, 0.0),
+            knockback: (0.0, 0.0),
+        }
+    }
+
+    // Update function to move the bug towards the pet.
+    // obstacles are the colliders near the bug; walking bugs steer around them, flyers hop over them.
+    pub fn update(&mut self, dt: f32, pet_x: f32, pet_z: f32, obstacles: &[Collider]) {
+        self.hit_cooldown = (self.hit_cooldown - dt).max(0.0);
+
+        // A bug knocked back by the ball slides away until it gets its footing back.
+        if self.knockback != (0.0, 0.0) {
+            let frames = dt * 60.0;
+            self.x += self.knockback.0 * frames;
+            self.z += self.knockback.1 * frames;
+            let grip = KNOCKBACK_GRIP.powf(frames);
+            self.knockback = (self.knockback.0 * grip, self.knockback.1 * grip);
+            if self.knockback.0.abs() + self.knockback.1.abs() < 0.001 {
+                self.knockback = (0.0, 0.0);
+            }
+        }
+
+        // Calculate the direction vector towards the pet.
+        let dx = pet_x - self.x;
+        let dz = pet_z - self.z;
+        let dist = (dx * dx + dz * dz).sqrt();
+
+        // Don't move if the bug is already at the pet's position.
+        if dist <= 0.1 {
+            return;
+        }
+        let to_pet = Vec2::new(dx / dist, dz / dist);
+        let mut speed = self.speed * self.kind.speed_factor();
+
+        let direction = match self.kind {
+            // Swarmers chase the pet, nudged by their flock.
+            BugKind::Swarmer => (to_pet + Vec2::from(self.flock)).normalize_or(to_pet),
+            BugKind::Flanker => {
+                if self.timer >= ORBIT_TIME {
+                    // Done circling: charge!
+                    speed *= CHARGE_FACTOR;
+                    to_pet
+                } else if dist < ORBIT_RADIUS * 1.5 {
+                    // Close enough: circle around the pet, correcting towards the orbit distance.
+                    self.timer += dt;
+                    let tangent = Vec2::new(-to_pet.y, to_pet.x) * self.orbit_dir;
+                    let correction = ((dist - ORBIT_RADIUS) * 0.5).clamp(-1.0, 1.0);
+                    (tangent + to_pet * correction).normalize_or(to_pet)
+                } else {
+                    // Still far away: approach like any other bug.
+                    to_pet
+                }
+            }
+            BugKind::Beetle => to_pet,
+            BugKind::Flyer => {
+                // Hop along: the height follows a bouncing sine wave.
+                self.timer += dt * HOP_RATE;
+                self.y = self.timer.sin().abs() * HOP_HEIGHT;
+                to_pet
+            }
+        };
+
+        let direction = if self.kind == BugKind::Flyer { direction } else { self.avoid(direction, obstacles) };
+
+        // Never step further than the remaining distance, so big ticks cannot overshoot the pet.
+        let step = (speed * dt * 60.0).min(dist);
+        let mut pos = Vec2::new(self.x + direction.x * step, self.z + direction.y * step);
+        if self.kind != BugKind::Flyer {
+            // Whatever the steering missed, the bug still cannot walk through.
+            terrain::push_out(&mut pos, self.kind.radius(), obstacles);
+        }
+        self.x = pos.x;
+        self.z = pos.y;
+        self.heading = direction.x.atan2(direction.y);
+    }
+
+    // Bend the walking direction around obstacles that are straight ahead, turning more
+    // the closer the bug would pass to the obstacle's middle.
+    fn avoid(&self, direction: Vec2, obstacles: &[Collider]) -> Vec2 {
+        let pos = Vec2::new(self.x, self.z);
+        let mut steer = Vec2::ZERO;
+        for obstacle in obstacles {
+            let to_obstacle = obstacle.center() - pos;
+            let ahead = to_obstacle.dot(direction);
+            if ahead <= 0.0 || ahead > LOOKAHEAD {
+                continue;
+            }
+            // How far to the side of the bug's path the obstacle is (positive is to the left).
+            let side = direction.perp();
+            let lateral = to_obstacle.dot(side);
+            let clearance = obstacle.bounding_radius() + self.kind.radius() + AVOID_MARGIN;
+            if lateral.abs() >= clearance {
+                continue;
+            }
+            // Dead ahead, go round the way the bug would circle anyway.
+            let away = if lateral.abs() < 0.001 { self.orbit_dir } else { -lateral.signum() };
+            steer += side * away * (1.0 - lateral.abs() / clearance) * 2.0;
+        }
+        (direction + steer).normalize_or(direction)
+    }
+
+    // Hit the bug once. Returns true if that squashed it.
+    // A bug that was hit a moment ago shrugs off further hits for a short while.
+    pub fn hit(&mut self) -> bool {
+        if self.hit_cooldown > 0.0 {
+            return false;
+        }
+        self.health = self.health.saturating_sub(1);
+        self.hit_cooldown = HIT_COOLDOWN;
+        self.health == 0
+    }
+}
+
+// The flocking steering of the swarmer at `index`: away from swarmers that are too close
+// (separation) and towards the middle of the nearby swarmers (cohesion).
+// The grid must have been built from `bugs`.
+pub fn flock_steering(bugs: &[Bug], grid: &SpatialGrid, index: usize) -> Vec2 {
+    let bug = &bugs[index];
+    let mut separation = Vec2::ZERO;
+    let mut centre = Vec2::ZERO;
+    let mut neighbours = 0;
+    for i in grid.nearby(bug.x, bug.z, FLOCK_RADIUS).take(MAX_NEIGHBOURS) {
+        let other = &bugs[i];
+        if i == index || other.kind != BugKind::Swarmer {
+            continue;
+        }
+        let offset = Vec2::new(bug.x - other.x, bug.z - other.z);
+        let dist_sq = offset.length_squared();
+        if dist_sq >= FLOCK_RADIUS * FLOCK_RADIUS {
+            continue;
+        }
+        if dist_sq < SEPARATION_RADIUS * SEPARATION_RADIUS && dist_sq > 0.0 {
+            separation += offset / dist_sq;
+        }
+        centre += Vec2::new(other.x, other.z);
+        neighbours += 1;
+    }
+    if neighbours == 0 {
+        return Vec2::ZERO;
+    }
+    let cohesion = (centre / neighbours as f32 - Vec2::new(bug.x, bug.z)).normalize_or_zero();
+    separation * 0.5 + cohesion * 0.3
+}
+
+// What is left of a bug after it was squashed. The pet can eat it for a bit of food.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SquashedBug {
+    // Where the bug was squashed.
+    pub x: f32,
+    pub z: f32,
+    // Seconds since the bug was squashed.
+    pub age: f32,
+}
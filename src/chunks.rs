@@ -0,0 +1,121 @@
+// The chunks of the world around the pet. Each chunk's features are generated once, when the
+// chunk first comes near the pet or is first needed by the simulation, and kept in a map until
+// the pet has moved far away from it again.
+use rustc_hash::FxHashMap;
+
+use crate::terrain::{self, Biome, Collider, Feature};
+
+// How many squares the ground of a chunk is split into along each side.
+// The height and the biome are sampled at every corner of those squares.
+pub const GROUND_STEPS: usize = 10;
+
+// One generated chunk of the world.
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    // The chunk coordinate.
+    pub cx: i32,
+    pub cz: i32,
+    // Everything growing or lying on the chunk.
+    pub features: Vec<Feature>,
+    // The colliders of the solid features, ready for the physics.
+    pub colliders: Vec<Collider>,
+    // The ground height at each of the (GROUND_STEPS + 1) x (GROUND_STEPS + 1) sample points,
+    // row by row along z. Heights below the water level are under water.
+    pub heights: Vec<f32>,
+    // The biome at each sample point, in the same order.
+    pub biomes: Vec<Biome>,
+}
+
+impl Chunk {
+    // The index of sample point (i, j) in heights and biomes: i steps along x, j along z.
+    pub fn sample_index(i: usize, j: usize) -> usize {
+        j * (GROUND_STEPS + 1) + i
+    }
+
+    // true if some of the ground in this chunk is under water.
+    pub fn has_water(&self) -> bool {
+        self.heights.iter().any(|&height| height < terrain::WATER_LEVEL)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChunkManager {
+    // The run seed the chunks are generated from.
+    seed: u64,
+    // Side length of one square chunk in world units.
+    chunk_size: f32,
+    // The generated chunks, keyed by chunk coordinate.
+    chunks: FxHashMap<(i32, i32), Chunk>,
+    // The chunk the pet was in at the last update, so eviction only runs when it changes.
+    center: Option<(i32, i32)>,
+}
+
+impl ChunkManager {
+    pub fn new(seed: u64, chunk_size: f32) -> ChunkManager {
+        ChunkManager { seed, chunk_size, chunks: FxHashMap::default(), center: None }
+    }
+
+    // true if the chunks were generated for this seed and chunk size.
+    pub fn matches(&self, seed: u64, chunk_size: f32) -> bool {
+        self.seed == seed && self.chunk_size == chunk_size
+    }
+
+    pub fn chunk_size(&self) -> f32 {
+        self.chunk_size
+    }
+
+    // The chunk a world position falls into.
+    pub fn chunk_of(&self, x: f32, z: f32) -> (i32, i32) {
+        ((x / self.chunk_size).floor() as i32, (z / self.chunk_size).floor() as i32)
+    }
+
+    // Follow the pet: generate every chunk within view_dist chunks of (x, z), and once the pet
+    // enters another chunk, forget the chunks further than keep_dist chunks away.
+    pub fn update(&mut self, x: f32, z: f32, view_dist: i32, keep_dist: i32) {
+        let (pcx, pcz) = self.chunk_of(x, z);
+        for cx in (pcx - view_dist)..=(pcx + view_dist) {
+            for cz in (pcz - view_dist)..=(pcz + view_dist) {
+                self.chunk(cx, cz);
+            }
+        }
+        if self.center != Some((pcx, pcz)) {
+            self.center = Some((pcx, pcz));
+            self.chunks.retain(|&(cx, cz), _| (cx - pcx).abs() <= keep_dist && (cz - pcz).abs() <= keep_dist);
+        }
+    }
+
+    // A chunk that has already been generated.
+    pub fn get(&self, cx: i32, cz: i32) -> Option<&Chunk> {
+        self.chunks.get(&(cx, cz))
+    }
+
+    // A chunk, generating it if it is not in the cache.
+    pub fn chunk(&mut self, cx: i32, cz: i32) -> &Chunk {
+        let (seed, chunk_size) = (self.seed, self.chunk_size);
+        self.chunks.entry((cx, cz)).or_insert_with(|| {
+            let features = terrain::generate_chunk(seed, cx, cz, chunk_size);
+            let colliders = features.iter().filter_map(Feature::collider).collect();
+            let step = chunk_size / GROUND_STEPS as f32;
+            let points: Vec<(f32, f32)> = (0..=GROUND_STEPS)
+                .flat_map(|j| (0..=GROUND_STEPS).map(move |i| (i, j)))
+                .map(|(i, j)| ((cx as f32 * chunk_size) + i as f32 * step, (cz as f32 * chunk_size) + j as f32 * step))
+                .collect();
+            let heights = points.iter().map(|&(x, z)| terrain::terrain_height(seed, x, z)).collect();
+            let biomes = points.iter().map(|&(x, z)| terrain::biome_at(seed, x, z)).collect();
+            Chunk { cx, cz, features, colliders, heights, biomes }
+        })
+    }
+
+    // Collect the colliders of every chunk touching the square around (x, z) into `out`.
+    // Like the spatial grid, this may include colliders a little further away than `radius`.
+    pub fn colliders_near(&mut self, x: f32, z: f32, radius: f32, out: &mut Vec<Collider>) {
+        out.clear();
+        let (min_x, min_z) = self.chunk_of(x - radius, z - radius);
+        let (max_x, max_z) = self.chunk_of(x + radius, z + radius);
+        for cx in min_x..=max_x {
+            for cz in min_z..=max_z {
+                out.extend_from_slice(&self.chunk(cx, cz).colliders);
+            }
+        }
+    }
+}
@@ -0,0 +1,1235 @@
+// Gameplay tuning values, loaded from a TOML file so they can be changed without recompiling.
+// Every value has a default matching the original hard-coded game, and any value missing
+// from the file falls back to its default.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::time::SystemTime;
+
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+use crate::bug::BugKind;
+use crate::inventory::ItemKind;
+use crate::species::Ability;
+use crate::weather::WeatherKind;
+
+// Where the configuration is read from unless another file is given on the command line.
+pub const CONFIG_PATH: &str = "config.toml";
+
+// Everything that can go wrong while loading the configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    // The file could not be read.
+    Io(io::Error),
+    // The file is not valid TOML or has fields of the wrong type.
+    Parse(toml::de::Error),
+    // The file parsed, but a value is outside of its allowed range.
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "could not read config: {}", e),
+            ConfigError::Parse(e) => write!(f, "config is not valid: {}", e),
+            ConfigError::Invalid(msg) => write!(f, "config value out of range: {}", msg),
+        }
+    }
+}
+
+// How bugs hurt the pet. How many bugs there are depends on the difficulty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BugConfig {
+    // A bug closer than this bites the pet, taking away health.
+    // Older config files call this kill_radius, from when one bite was fatal.
+    #[serde(alias = "kill_radius")]
+    pub contact_radius: f32,
+    // A bug closer than this drains the pet's stats.
+    pub threat_radius: f32,
+    // A bug further than this from the pet is removed from the world.
+    pub despawn_distance: f32,
+}
+
+impl Default for BugConfig {
+    fn default() -> BugConfig {
+        BugConfig { contact_radius: 1.0, threat_radius: 2.0, despawn_distance: 120.0 }
+    }
+}
+
+// The difficulty levels that can be picked on the start screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    // No bugs at all, just pet care.
+    Peaceful,
+    Casual,
+    #[default]
+    Normal,
+    // The original "67 BUGS/S" mode.
+    Nightmare,
+}
+
+impl Difficulty {
+    // Every difficulty, from easiest to hardest.
+    pub const ALL: [Difficulty; 4] = [Difficulty::Peaceful, Difficulty::Casual, Difficulty::Normal, Difficulty::Nightmare];
+
+    // The name shown on the start screen and in the HUD.
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Peaceful => "PEACEFUL",
+            Difficulty::Casual => "CASUAL",
+            Difficulty::Normal => "NORMAL",
+            Difficulty::Nightmare => "NIGHTMARE",
+        }
+    }
+
+    // The next harder (step = 1) or easier (step = -1) difficulty, stopping at the ends.
+    pub fn step(self, step: i32) -> Difficulty {
+        let index = Difficulty::ALL.iter().position(|d| *d == self).unwrap_or(0) as i32;
+        Difficulty::ALL[(index + step).clamp(0, Difficulty::ALL.len() as i32 - 1) as usize]
+    }
+}
+
+// How bugs spawn and speed up for one difficulty level.
+// A preset table in the config file must list every value except the weights.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DifficultyConfig {
+    // How many bugs appear in each wave.
+    pub spawn_count: u32,
+    // Seconds between two waves.
+    pub spawn_interval: f32,
+    // How far from the pet new bugs appear.
+    pub spawn_distance: f32,
+    // Bug speed at the start of a run, in units per 60Hz frame.
+    pub start_speed: f32,
+    // How much faster bugs get every time the speed increases.
+    pub speed_increase: f32,
+    // Seconds between two speed increases.
+    pub speed_increase_interval: f32,
+    // No more bugs spawn while this many are alive.
+    pub max_bugs: u32,
+    // How likely each kind of bug is to spawn. Tables written before bug kinds existed only spawn swarmers.
+    #[serde(default)]
+    pub weights: BugWeights,
+}
+
+// Relative spawn chances of the bug kinds, e.g. swarmer = 3 and beetle = 1 spawns three swarmers per beetle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BugWeights {
+    pub swarmer: u32,
+    pub flanker: u32,
+    pub beetle: u32,
+    pub flyer: u32,
+}
+
+impl Default for BugWeights {
+    fn default() -> BugWeights {
+        BugWeights { swarmer: 1, flanker: 0, beetle: 0, flyer: 0 }
+    }
+}
+
+impl BugWeights {
+    // The weight of one kind of bug.
+    pub fn get(&self, kind: BugKind) -> u32 {
+        match kind {
+            BugKind::Swarmer => self.swarmer,
+            BugKind::Flanker => self.flanker,
+            BugKind::Beetle => self.beetle,
+            BugKind::Flyer => self.flyer,
+        }
+    }
+
+    // The sum of all weights.
+    pub fn total(&self) -> u32 {
+        BugKind::ALL.iter().map(|kind| self.get(*kind)).sum()
+    }
+
+    // Pick the kind of bug for a roll between 0 and total().
+    pub fn pick(&self, roll: u32) -> BugKind {
+        let mut roll = roll;
+        for kind in BugKind::ALL {
+            let weight = self.get(kind);
+            if roll < weight {
+                return kind;
+            }
+            roll -= weight;
+        }
+        BugKind::Swarmer
+    }
+}
+
+impl DifficultyConfig {
+    // The HUD label, e.g. "DIFFICULTY: NIGHTMARE (67 BUGS/S)".
+    pub fn label(&self, difficulty: Difficulty) -> String {
+        if self.spawn_count == 0 || self.max_bugs == 0 {
+            format!("DIFFICULTY: {} (NO BUGS)", difficulty.name())
+        } else {
+            let per_second = self.spawn_count as f32 / self.spawn_interval;
+            format!("DIFFICULTY: {} ({} BUGS/S)", difficulty.name(), per_second)
+        }
+    }
+}
+
+// The settings of every difficulty level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyPresets {
+    pub peaceful: DifficultyConfig,
+    pub casual: DifficultyConfig,
+    pub normal: DifficultyConfig,
+    pub nightmare: DifficultyConfig,
+}
+
+impl Default for DifficultyPresets {
+    fn default() -> DifficultyPresets {
+        DifficultyPresets {
+            peaceful: DifficultyConfig {
+                spawn_count: 0,
+                spawn_interval: 1.0,
+                spawn_distance: 50.0,
+                start_speed: 0.0,
+                speed_increase: 0.0,
+                speed_increase_interval: 5.0,
+                max_bugs: 0,
+                weights: BugWeights { swarmer: 0, flanker: 0, beetle: 0, flyer: 0 },
+            },
+            casual: DifficultyConfig {
+                spawn_count: 1,
+                spawn_interval: 2.0,
+                spawn_distance: 60.0,
+                start_speed: 0.06,
+                speed_increase: 0.005,
+                speed_increase_interval: 10.0,
+                max_bugs: 30,
+                weights: BugWeights { swarmer: 6, flanker: 1, beetle: 3, flyer: 0 },
+            },
+            normal: DifficultyConfig {
+                spawn_count: 5,
+                spawn_interval: 1.0,
+                spawn_distance: 55.0,
+                start_speed: 0.09,
+                speed_increase: 0.01,
+                speed_increase_interval: 7.0,
+                max_bugs: 200,
+                weights: BugWeights { swarmer: 5, flanker: 2, beetle: 2, flyer: 1 },
+            },
+            nightmare: DifficultyConfig {
+                spawn_count: 67,
+                spawn_interval: 1.0,
+                spawn_distance: 50.0,
+                start_speed: 0.12,
+                speed_increase: 0.02,
+                speed_increase_interval: 5.0,
+                max_bugs: 5000,
+                weights: BugWeights { swarmer: 6, flanker: 2, beetle: 1, flyer: 1 },
+            },
+        }
+    }
+}
+
+impl DifficultyPresets {
+    // The settings for one difficulty level.
+    pub fn get(&self, difficulty: Difficulty) -> &DifficultyConfig {
+        match difficulty {
+            Difficulty::Peaceful => &self.peaceful,
+            Difficulty::Casual => &self.casual,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Nightmare => &self.nightmare,
+        }
+    }
+}
+
+// How the world around the pet is split into chunks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerrainConfig {
+    // Side length of one square chunk in world units. Changing it changes the layout of the world.
+    pub chunk_size: f32,
+    // How many chunks are generated and drawn in each direction around the pet.
+    pub view_dist: u32,
+}
+
+impl Default for TerrainConfig {
+    fn default() -> TerrainConfig {
+        TerrainConfig { chunk_size: 20.0, view_dist: 2 }
+    }
+}
+
+// How the pet and the ball move.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MovementConfig {
+    // Fraction of velocity kept every 60Hz frame, for both the pet and the ball.
+    pub friction: f32,
+    // Velocity the pet gains every 60Hz frame while a movement key is held.
+    pub accel: f32,
+    // How fast the pet turns in third person, in radians per 60Hz frame.
+    pub rotation_speed: f32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> MovementConfig {
+        MovementConfig { friction: 0.95, accel: 0.02, rotation_speed: 0.05 }
+    }
+}
+
+// How much faster the pet runs while sprinting and what it costs, and the dash for getting away
+// from bugs in a hurry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SprintConfig {
+    // Factor on the pet's acceleration while sprinting.
+    pub accel_factor: f32,
+    // Energy used up and hunger gained every second of sprinting, on top of moving.
+    pub energy_per_second: f32,
+    pub hunger_per_second: f32,
+    // Speed a dash launches the pet with, in units per 60Hz frame.
+    pub dash_speed: f32,
+    // Energy a dash costs. The pet cannot dash with less energy than this.
+    pub dash_energy: f32,
+    // Seconds after a dash before the pet can dash again.
+    pub dash_cooldown: f32,
+}
+
+impl Default for SprintConfig {
+    fn default() -> SprintConfig {
+        SprintConfig {
+            accel_factor: 1.8,
+            energy_per_second: 1.5,
+            hunger_per_second: 0.5,
+            dash_speed: 0.8,
+            dash_energy: 5.0,
+            dash_cooldown: 3.0,
+        }
+    }
+}
+
+// How high the pet jumps and what it costs. A pet with plenty of energy can jump again in mid-air.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JumpConfig {
+    // Upward speed of a jump, in units per 60Hz frame.
+    pub speed: f32,
+    // Upward speed lost every 60Hz frame in the air.
+    pub gravity: f32,
+    // Energy every jump costs. The pet cannot jump with less energy than this.
+    pub energy_cost: f32,
+    // The pet can jump a second time in mid-air while it has at least this much energy.
+    pub double_jump_energy: f32,
+}
+
+impl Default for JumpConfig {
+    fn default() -> JumpConfig {
+        JumpConfig { speed: 0.22, gravity: 0.015, energy_cost: 2.0, double_jump_energy: 60.0 }
+    }
+}
+
+// How the sleep action works. Feeding and playing depend on the item used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ActionConfig {
+    // Energy regained every second while the pet is asleep.
+    pub sleep_energy: f32,
+    // Keys of older configuration files, from before feeding and playing depended on the item
+    // used and sleeping made the pet hungry. They are still accepted so those files keep
+    // loading, but do nothing.
+    #[serde(rename = "feed_hunger", default, skip_serializing)]
+    _feed_hunger: IgnoredAny,
+    #[serde(rename = "play_happiness", default, skip_serializing)]
+    _play_happiness: IgnoredAny,
+    #[serde(rename = "play_energy_cost", default, skip_serializing)]
+    _play_energy_cost: IgnoredAny,
+    #[serde(rename = "sleep_hunger", default, skip_serializing)]
+    _sleep_hunger: IgnoredAny,
+}
+
+impl Default for ActionConfig {
+    fn default() -> ActionConfig {
+        ActionConfig {
+            sleep_energy: 4.0,
+            _feed_hunger: IgnoredAny,
+            _play_happiness: IgnoredAny,
+            _play_energy_cost: IgnoredAny,
+            _sleep_hunger: IgnoredAny,
+        }
+    }
+}
+
+// Limits that keep the aging while the game was closed from being too harsh: after a long break
+// the pet is hungry and sad instead of dead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OfflineConfig {
+    // Offline hunger never pushes the pet above this value.
+    pub hunger_cap: f32,
+    // Offline sadness never pushes happiness below this value.
+    pub happiness_floor: f32,
+    // At most this many seconds of absence are simulated.
+    pub max_elapsed: f32,
+}
+
+impl Default for OfflineConfig {
+    fn default() -> OfflineConfig {
+        // At most three days.
+        OfflineConfig { hunger_cap: 90.0, happiness_floor: 10.0, max_elapsed: 3.0 * 24.0 * 60.0 * 60.0 }
+    }
+}
+
+// When the pet gets into each mood. A mood starts at its threshold and lasts until the stat has
+// moved margin past it the other way, so the pet's mood does not flicker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MoodConfig {
+    // The pet gets scared of a bug closer than scare_radius, and calms down once none is within calm_radius.
+    pub scare_radius: f32,
+    pub calm_radius: f32,
+    // Sleepy below this energy.
+    pub sleepy_energy: f32,
+    // Grumpy above this hunger.
+    pub grumpy_hunger: f32,
+    // Playful above both this happiness and this energy.
+    pub playful_happiness: f32,
+    pub playful_energy: f32,
+    // Bored below this happiness.
+    pub bored_happiness: f32,
+    pub margin: f32,
+}
+
+impl Default for MoodConfig {
+    fn default() -> MoodConfig {
+        MoodConfig {
+            scare_radius: 6.0,
+            calm_radius: 10.0,
+            sleepy_energy: 25.0,
+            grumpy_hunger: 70.0,
+            playful_happiness: 75.0,
+            playful_energy: 50.0,
+            bored_happiness: 30.0,
+            margin: 10.0,
+        }
+    }
+}
+
+// When the pet grows up, and what it grows into.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GrowthConfig {
+    // Age in seconds at which the egg hatches into a baby, the baby becomes a child,
+    // the child an adult, and the adult an elder.
+    pub hatch_age: f32,
+    pub child_age: f32,
+    pub adult_age: f32,
+    pub elder_age: f32,
+    // Average care, from 0 to 100, needed to grow into a radiant adult, and below which it grows
+    // into a gloomy one. Anything in between grows into a sturdy adult.
+    pub radiant_care: f32,
+    pub gloomy_care: f32,
+}
+
+impl Default for GrowthConfig {
+    fn default() -> GrowthConfig {
+        GrowthConfig {
+            hatch_age: 20.0,
+            child_age: 90.0,
+            adult_age: 240.0,
+            elder_age: 900.0,
+            radiant_care: 65.0,
+            gloomy_care: 40.0,
+        }
+    }
+}
+
+// The day and night cycle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DayConfig {
+    // Seconds of play for one full day.
+    pub length: f32,
+    // The hour of the day a new run starts at, from 0 up to 24.
+    pub start_hour: f32,
+    // In the middle of the night bugs spawn this many times as often as in the day...
+    pub night_spawn_factor: f32,
+    // ...and move this many times as fast.
+    pub night_speed_factor: f32,
+}
+
+impl Default for DayConfig {
+    fn default() -> DayConfig {
+        DayConfig { length: 300.0, start_hour: 8.0, night_spawn_factor: 2.0, night_speed_factor: 1.3 }
+    }
+}
+
+// The weather that rolls through the world and what it does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeatherConfig {
+    // Every spell of weather lasts between these many seconds.
+    pub min_duration: f32,
+    pub max_duration: f32,
+    // How likely each kind of weather is to come next.
+    pub weights: WeatherWeights,
+    // Fraction of velocity kept every 60Hz frame on wet ground, instead of movement.friction.
+    pub rain_friction: f32,
+    // Extra happiness lost every second in the rain.
+    pub rain_happiness: f32,
+    // Velocity the wind adds to the ball every 60Hz frame.
+    pub wind_strength: f32,
+    // Hunger and energy drain this many times as fast in a heat wave.
+    pub heat_drain: f32,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> WeatherConfig {
+        WeatherConfig {
+            min_duration: 40.0,
+            max_duration: 100.0,
+            weights: WeatherWeights::default(),
+            rain_friction: 0.975,
+            rain_happiness: 0.5,
+            wind_strength: 0.001,
+            heat_drain: 1.75,
+        }
+    }
+}
+
+// Relative chances of each kind of weather coming next.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeatherWeights {
+    pub clear: u32,
+    pub rain: u32,
+    pub wind: u32,
+    pub heat: u32,
+}
+
+impl Default for WeatherWeights {
+    fn default() -> WeatherWeights {
+        WeatherWeights { clear: 4, rain: 2, wind: 2, heat: 1 }
+    }
+}
+
+impl WeatherWeights {
+    // The weight of one kind of weather.
+    pub fn get(&self, kind: WeatherKind) -> u32 {
+        match kind {
+            WeatherKind::Clear => self.clear,
+            WeatherKind::Rain => self.rain,
+            WeatherKind::Wind => self.wind,
+            WeatherKind::Heat => self.heat,
+        }
+    }
+
+    // The sum of all weights.
+    pub fn total(&self) -> u32 {
+        WeatherKind::ALL.iter().map(|kind| self.get(*kind)).sum()
+    }
+}
+
+// What one kind of item does when it is used, and how often it is found.
+// Effects left out of an item's table are 0.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemConfig {
+    // Hunger taken away.
+    pub nutrition: f32,
+    // Happiness, energy and health gained. Negative values take them away.
+    pub happiness: f32,
+    pub energy: f32,
+    pub health: f32,
+    // Relative chance of this item being the next one to appear on the ground.
+    pub spawn_weight: u32,
+    // How many of this item a new pet starts with.
+    pub start: u32,
+}
+
+// The items lying around the world and what each of them does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemsConfig {
+    // Items closer to the pet than this are picked up.
+    pub pickup_radius: f32,
+    // The most items of one kind the pet can carry.
+    pub max_stack: u32,
+    // Seconds between two items appearing on the ground.
+    pub spawn_interval: f32,
+    // How far from the pet new items appear.
+    pub spawn_min_distance: f32,
+    pub spawn_max_distance: f32,
+    // No more items appear while this many are lying on the ground.
+    pub max_ground: u32,
+    pub berry: ItemConfig,
+    pub apple: ItemConfig,
+    pub mushroom: ItemConfig,
+    pub stick: ItemConfig,
+    pub feather: ItemConfig,
+    pub medicine: ItemConfig,
+}
+
+impl Default for ItemsConfig {
+    fn default() -> ItemsConfig {
+        let item = ItemConfig::default();
+        ItemsConfig {
+            pickup_radius: 1.5,
+            max_stack: 9,
+            spawn_interval: 15.0,
+            spawn_min_distance: 10.0,
+            spawn_max_distance: 30.0,
+            max_ground: 8,
+            berry: ItemConfig { nutrition: 15.0, start: 3, ..item.clone() },
+            apple: ItemConfig { nutrition: 30.0, happiness: 5.0, spawn_weight: 4, ..item.clone() },
+            mushroom: ItemConfig { nutrition: 8.0, energy: 15.0, spawn_weight: 3, ..item.clone() },
+            stick: ItemConfig { happiness: 15.0, energy: -10.0, spawn_weight: 2, start: 1, ..item.clone() },
+            feather: ItemConfig { happiness: 25.0, energy: -15.0, spawn_weight: 1, ..item.clone() },
+            medicine: ItemConfig { happiness: -5.0, health: 40.0, spawn_weight: 1, ..item },
+        }
+    }
+}
+
+impl ItemsConfig {
+    // The settings for one kind of item.
+    pub fn get(&self, kind: ItemKind) -> &ItemConfig {
+        match kind {
+            ItemKind::Berry => &self.berry,
+            ItemKind::Apple => &self.apple,
+            ItemKind::Mushroom => &self.mushroom,
+            ItemKind::Stick => &self.stick,
+            ItemKind::Feather => &self.feather,
+            ItemKind::Medicine => &self.medicine,
+        }
+    }
+
+    // The sum of all spawn weights.
+    pub fn total_weight(&self) -> u32 {
+        ItemKind::ALL.iter().map(|kind| self.get(*kind).spawn_weight).sum()
+    }
+
+    // Pick the kind of item for a roll between 0 and total_weight().
+    pub fn pick(&self, roll: u32) -> ItemKind {
+        let mut roll = roll;
+        for kind in ItemKind::ALL {
+            let weight = self.get(kind).spawn_weight;
+            if roll < weight {
+                return kind;
+            }
+            roll -= weight;
+        }
+        ItemKind::Apple
+    }
+}
+
+// How the pet finds food and water in the world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForageConfig {
+    // How close a flower or puddle has to be to pick a berry or drink.
+    pub reach: f32,
+    // Seconds before a picked flower has a new berry.
+    pub berry_regrow: f32,
+    // Seconds before a puddle drunk dry has filled up again.
+    pub puddle_regrow: f32,
+    // Energy regained by drinking from a puddle.
+    pub drink_energy: f32,
+}
+
+impl Default for ForageConfig {
+    fn default() -> ForageConfig {
+        ForageConfig { reach: 2.0, berry_regrow: 90.0, puddle_regrow: 45.0, drink_energy: 10.0 }
+    }
+}
+
+// The fetch mini-game with the ball, and playing with the ball without a toy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FetchConfig {
+    // Seconds the throw key has to be held for a throw at full power.
+    pub charge_time: f32,
+    // Speed of the ball thrown with no power and with full power, in units per 60Hz frame.
+    pub min_speed: f32,
+    pub max_speed: f32,
+    // The upward speed of a throw, as a fraction of its forward speed.
+    pub loft: f32,
+    // How close the pet has to get to the ball to grab it.
+    pub reach: f32,
+    // How close to the spot it was thrown from the pet has to bring the ball back.
+    pub return_radius: f32,
+    // Happiness for every unit the ball was thrown, then multiplied by 1 + streak_bonus for every
+    // fetch in a row before this one, up to max_happiness for a single fetch.
+    pub distance_happiness: f32,
+    pub streak_bonus: f32,
+    pub max_happiness: f32,
+    // Seconds before the pet gives up on a fetch, losing its streak.
+    pub timeout: f32,
+    // Without a toy, the pet can play with the ball while it is this close.
+    pub play_radius: f32,
+    // Happiness gained and energy spent playing with the ball.
+    pub play_happiness: f32,
+    pub play_energy: f32,
+}
+
+impl Default for FetchConfig {
+    fn default() -> FetchConfig {
+        FetchConfig {
+            charge_time: 1.0,
+            min_speed: 0.2,
+            max_speed: 0.6,
+            loft: 0.5,
+            reach: 1.5,
+            return_radius: 2.0,
+            distance_happiness: 1.0,
+            streak_bonus: 0.25,
+            max_happiness: 40.0,
+            timeout: 30.0,
+            play_radius: 3.0,
+            play_happiness: 8.0,
+            play_energy: 5.0,
+        }
+    }
+}
+
+// How the pet fights back against bugs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CombatConfig {
+    // Bugs closer than this to the pet are squashed by a pounce.
+    pub pounce_radius: f32,
+    // Energy a pounce costs. The pet cannot pounce with less energy than this.
+    pub pounce_energy_cost: f32,
+    // The ball squashes bugs it touches while moving faster than this, in units per 60Hz frame.
+    pub ball_kill_speed: f32,
+    // Bugs the ball runs into are knocked back with this fraction of the ball's speed.
+    pub ball_knockback: f32,
+    // How far a squashed bug can be from the pet and still be eaten.
+    pub eat_radius: f32,
+    // Hunger removed by eating one squashed bug.
+    pub eat_hunger: f32,
+    // Seconds a squashed bug stays on the ground before it disappears.
+    pub squashed_lifetime: f32,
+}
+
+impl Default for CombatConfig {
+    fn default() -> CombatConfig {
+        CombatConfig {
+            pounce_radius: 3.0,
+            pounce_energy_cost: 8.0,
+            ball_kill_speed: 0.1,
+            ball_knockback: 0.5,
+            eat_radius: 1.5,
+            eat_hunger: 4.0,
+            squashed_lifetime: 20.0,
+        }
+    }
+}
+
+// How the pet gets hurt by bugs and heals again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    // Health taken away by one bug bite.
+    pub bug_damage: f32,
+    // Seconds after a bite during which the pet cannot be bitten again.
+    pub invulnerability: f32,
+    // Speed the pet is knocked away from the bug with, in units per 60Hz frame.
+    pub knockback: f32,
+    // Health regained every second while the pet is well fed and happy.
+    pub regen_per_second: f32,
+    // The pet only heals while its hunger is below this...
+    pub regen_max_hunger: f32,
+    // ...and its happiness is above this.
+    pub regen_min_happiness: f32,
+    // Bites on a sleeping pet do this many times the damage, and wake it up.
+    pub sleep_damage: f32,
+}
+
+impl Default for HealthConfig {
+    fn default() -> HealthConfig {
+        HealthConfig {
+            bug_damage: 20.0,
+            invulnerability: 1.0,
+            knockback: 0.4,
+            regen_per_second: 2.0,
+            regen_max_hunger: 40.0,
+            regen_min_happiness: 60.0,
+            sleep_damage: 2.0,
+        }
+    }
+}
+
+// One shape the pet's model is built from, on top of its round body. Positions are given as
+// [sideways, up, forward] from the centre of the body, for a grown-up pet with a body radius of 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Part {
+    Sphere {
+        at: [f32; 3],
+        radius: f32,
+        color: [f32; 3],
+        // Rolling parts turn with the body as the pet rolls along.
+        #[serde(default)]
+        rolls: bool,
+    },
+    // Cubes always stay lined up with the world axes, so they work best for small or round-ish parts.
+    Cube { at: [f32; 3], size: [f32; 3], color: [f32; 3] },
+}
+
+// A kind of pet that can be picked on the start screen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpeciesConfig {
+    // The name shown on the start screen, also used to find the species of a saved pet.
+    pub name: String,
+    // The stats a new pet of this species starts with.
+    pub hunger: f32,
+    pub happiness: f32,
+    pub energy: f32,
+    // Factors on how fast hunger and happiness drain, and on the energy spent moving.
+    pub hunger_decay: f32,
+    pub happiness_decay: f32,
+    pub energy_decay: f32,
+    // Factor on how fast the pet accelerates and turns.
+    pub speed: f32,
+    pub ability: Ability,
+    // The colour of the body, as red, green and blue from 0 to 1.
+    pub color: [f32; 3],
+    pub parts: Vec<Part>,
+}
+
+impl Default for SpeciesConfig {
+    fn default() -> SpeciesConfig {
+        SpeciesConfig {
+            name: "blob".to_string(),
+            hunger: 50.0,
+            happiness: 50.0,
+            energy: 50.0,
+            hunger_decay: 1.0,
+            happiness_decay: 1.0,
+            energy_decay: 1.0,
+            speed: 1.0,
+            ability: Ability::None,
+            color: [1.0, 0.63, 0.0],
+            parts: Vec::new(),
+        }
+    }
+}
+
+// Every species that can be picked, in the order they are listed on the start screen.
+// A config file that lists any species replaces the whole list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SpeciesList(pub Vec<SpeciesConfig>);
+
+impl Default for SpeciesList {
+    fn default() -> SpeciesList {
+        let species = SpeciesConfig::default();
+        let sphere = |at: [f32; 3], radius: f32, color: [f32; 3]| Part::Sphere { at, radius, color, rolls: false };
+        SpeciesList(vec![
+            // The original pet: an orange ball with a yellow and a brown spot that roll along with it.
+            SpeciesConfig {
+                ability: Ability::Glutton,
+                parts: vec![
+                    Part::Sphere { at: [0.0, 0.0, 0.8], radius: 0.2, color: [0.99, 0.98, 0.0], rolls: true },
+                    Part::Sphere { at: [0.0, 0.0, -0.8], radius: 0.2, color: [0.5, 0.42, 0.31], rolls: true },
+                ],
+                ..species.clone()
+            },
+            SpeciesConfig {
+                name: "cat".to_string(),
+                happiness: 60.0,
+                energy: 60.0,
+                hunger_decay: 1.1,
+                happiness_decay: 1.2,
+                speed: 1.2,
+                ability: Ability::Hunter,
+                color: [0.6, 0.5, 0.4],
+                parts: vec![
+                    Part::Cube { at: [0.45, 0.85, 0.2], size: [0.25, 0.4, 0.25], color: [0.5, 0.4, 0.3] },
+                    Part::Cube { at: [-0.45, 0.85, 0.2], size: [0.25, 0.4, 0.25], color: [0.5, 0.4, 0.3] },
+                    sphere([0.0, 0.2, -1.05], 0.2, [0.5, 0.4, 0.3]),
+                    sphere([0.0, 0.45, -1.3], 0.17, [0.5, 0.4, 0.3]),
+                    sphere([0.0, 0.75, -1.4], 0.14, [0.5, 0.4, 0.3]),
+                ],
+                ..species.clone()
+            },
+            SpeciesConfig {
+                name: "bird".to_string(),
+                happiness: 60.0,
+                energy: 40.0,
+                hunger_decay: 1.3,
+                energy_decay: 1.2,
+                speed: 1.3,
+                ability: Ability::Forager,
+                color: [0.3, 0.6, 0.95],
+                parts: vec![
+                    sphere([0.0, 0.0, 0.95], 0.2, [1.0, 0.63, 0.0]),
+                    sphere([0.95, 0.1, -0.1], 0.45, [0.2, 0.45, 0.8]),
+                    sphere([-0.95, 0.1, -0.1], 0.45, [0.2, 0.45, 0.8]),
+                    sphere([0.0, 1.0, 0.1], 0.2, [0.2, 0.45, 0.8]),
+                    sphere([0.0, 1.15, -0.1], 0.15, [0.2, 0.45, 0.8]),
+                ],
+                ..species.clone()
+            },
+            SpeciesConfig {
+                name: "turtle".to_string(),
+                hunger: 45.0,
+                energy: 60.0,
+                hunger_decay: 0.8,
+                happiness_decay: 0.9,
+                energy_decay: 0.8,
+                speed: 0.7,
+                ability: Ability::Shell,
+                color: [0.4, 0.7, 0.35],
+                parts: vec![
+                    sphere([0.0, 0.45, -0.35], 0.9, [0.2, 0.4, 0.15]),
+                    sphere([0.7, -0.8, 0.5], 0.25, [0.4, 0.7, 0.35]),
+                    sphere([-0.7, -0.8, 0.5], 0.25, [0.4, 0.7, 0.35]),
+                    sphere([0.7, -0.8, -0.5], 0.25, [0.4, 0.7, 0.35]),
+                    sphere([-0.7, -0.8, -0.5], 0.25, [0.4, 0.7, 0.35]),
+                ],
+                ..species
+            },
+        ])
+    }
+}
+
+impl SpeciesList {
+    // The species with the given name. Pets of a species that is no longer listed
+    // fall back to the first one.
+    pub fn get(&self, name: &str) -> &SpeciesConfig {
+        self.0.iter().find(|species| species.name == name).unwrap_or(&self.0[0])
+    }
+}
+
+// The whole configuration file, one table per section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub bugs: BugConfig,
+    pub difficulty: DifficultyPresets,
+    pub movement: MovementConfig,
+    pub sprint: SprintConfig,
+    pub jump: JumpConfig,
+    pub actions: ActionConfig,
+    pub day: DayConfig,
+    pub weather: WeatherConfig,
+    pub mood: MoodConfig,
+    pub growth: GrowthConfig,
+    pub species: SpeciesList,
+    pub combat: CombatConfig,
+    pub health: HealthConfig,
+    pub offline: OfflineConfig,
+    pub terrain: TerrainConfig,
+    pub forage: ForageConfig,
+    pub items: ItemsConfig,
+    pub fetch: FetchConfig,
+}
+
+impl Config {
+    // Parse a configuration from TOML text and check that every value makes sense.
+    pub fn parse(text: &str) -> Result<Config, ConfigError> {
+        let config: Config = toml::from_str(text).map_err(ConfigError::Parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Load the configuration from a file. A missing file simply means "use the defaults".
+    pub fn load(path: &str) -> Result<Config, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Config::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(ConfigError::Io(e)),
+        }
+    }
+
+    // Reject values that would break the game, naming the offending key.
+    fn validate(&self) -> Result<(), ConfigError> {
+        let mut positive = vec![
+            ("bugs.contact_radius".to_string(), self.bugs.contact_radius),
+            ("bugs.threat_radius".to_string(), self.bugs.threat_radius),
+            ("bugs.despawn_distance".to_string(), self.bugs.despawn_distance),
+            ("combat.pounce_radius".to_string(), self.combat.pounce_radius),
+            ("combat.eat_radius".to_string(), self.combat.eat_radius),
+            ("combat.squashed_lifetime".to_string(), self.combat.squashed_lifetime),
+            ("terrain.chunk_size".to_string(), self.terrain.chunk_size),
+            ("forage.reach".to_string(), self.forage.reach),
+            ("fetch.charge_time".to_string(), self.fetch.charge_time),
+            ("fetch.reach".to_string(), self.fetch.reach),
+            ("fetch.return_radius".to_string(), self.fetch.return_radius),
+            ("fetch.timeout".to_string(), self.fetch.timeout),
+            ("fetch.play_radius".to_string(), self.fetch.play_radius),
+            ("items.pickup_radius".to_string(), self.items.pickup_radius),
+            ("items.spawn_interval".to_string(), self.items.spawn_interval),
+            ("day.length".to_string(), self.day.length),
+            ("day.night_spawn_factor".to_string(), self.day.night_spawn_factor),
+            ("day.night_speed_factor".to_string(), self.day.night_speed_factor),
+            ("weather.min_duration".to_string(), self.weather.min_duration),
+            ("weather.max_duration".to_string(), self.weather.max_duration),
+            ("weather.heat_drain".to_string(), self.weather.heat_drain),
+            ("mood.scare_radius".to_string(), self.mood.scare_radius),
+            ("mood.calm_radius".to_string(), self.mood.calm_radius),
+            ("sprint.accel_factor".to_string(), self.sprint.accel_factor),
+            ("sprint.dash_cooldown".to_string(), self.sprint.dash_cooldown),
+            ("jump.speed".to_string(), self.jump.speed),
+            ("jump.gravity".to_string(), self.jump.gravity),
+        ];
+        let mut non_negative = vec![];
+        for difficulty in Difficulty::ALL {
+            let preset = self.difficulty.get(difficulty);
+            let key = |field: &str| format!("difficulty.{}.{}", difficulty.name().to_lowercase(), field);
+            positive.push((key("spawn_interval"), preset.spawn_interval));
+            positive.push((key("spawn_distance"), preset.spawn_distance));
+            positive.push((key("speed_increase_interval"), preset.speed_increase_interval));
+            non_negative.push((key("start_speed"), preset.start_speed));
+            non_negative.push((key("speed_increase"), preset.speed_increase));
+        }
+        for kind in ItemKind::ALL {
+            non_negative.push((format!("items.{}.health", kind.name()), self.items.get(kind).health));
+        }
+        for species in &self.species.0 {
+            let key = |field: &str| format!("species.{}.{}", species.name, field);
+            positive.push((key("speed"), species.speed));
+            non_negative.push((key("hunger_decay"), species.hunger_decay));
+            non_negative.push((key("happiness_decay"), species.happiness_decay));
+            non_negative.push((key("energy_decay"), species.energy_decay));
+            for part in &species.parts {
+                let sizes = match part {
+                    Part::Sphere { radius, .. } => vec![*radius],
+                    Part::Cube { size, .. } => size.to_vec(),
+                };
+                non_negative.extend(sizes.into_iter().map(|size| (key("parts.size"), size)));
+            }
+        }
+        for (key, value) in positive {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(ConfigError::Invalid(format!("{} must be greater than 0 (got {})", key, value)));
+            }
+        }
+        non_negative.extend([
+            ("movement.accel", self.movement.accel),
+            ("movement.rotation_speed", self.movement.rotation_speed),
+            ("sprint.energy_per_second", self.sprint.energy_per_second),
+            ("sprint.hunger_per_second", self.sprint.hunger_per_second),
+            ("sprint.dash_speed", self.sprint.dash_speed),
+            ("sprint.dash_energy", self.sprint.dash_energy),
+            ("jump.energy_cost", self.jump.energy_cost),
+            ("jump.double_jump_energy", self.jump.double_jump_energy),
+            ("actions.sleep_energy", self.actions.sleep_energy),
+            ("combat.pounce_energy_cost", self.combat.pounce_energy_cost),
+            ("combat.ball_kill_speed", self.combat.ball_kill_speed),
+            ("combat.ball_knockback", self.combat.ball_knockback),
+            ("combat.eat_hunger", self.combat.eat_hunger),
+            ("health.bug_damage", self.health.bug_damage),
+            ("health.invulnerability", self.health.invulnerability),
+            ("health.knockback", self.health.knockback),
+            ("health.regen_per_second", self.health.regen_per_second),
+            ("health.sleep_damage", self.health.sleep_damage),
+            ("offline.max_elapsed", self.offline.max_elapsed),
+            ("weather.rain_happiness", self.weather.rain_happiness),
+            ("weather.wind_strength", self.weather.wind_strength),
+            ("mood.sleepy_energy", self.mood.sleepy_energy),
+            ("mood.grumpy_hunger", self.mood.grumpy_hunger),
+            ("mood.playful_happiness", self.mood.playful_happiness),
+            ("mood.playful_energy", self.mood.playful_energy),
+            ("mood.bored_happiness", self.mood.bored_happiness),
+            ("mood.margin", self.mood.margin),
+            ("growth.hatch_age", self.growth.hatch_age),
+            ("growth.radiant_care", self.growth.radiant_care),
+            ("growth.gloomy_care", self.growth.gloomy_care),
+            ("forage.berry_regrow", self.forage.berry_regrow),
+            ("forage.puddle_regrow", self.forage.puddle_regrow),
+            ("forage.drink_energy", self.forage.drink_energy),
+            ("fetch.min_speed", self.fetch.min_speed),
+            ("fetch.loft", self.fetch.loft),
+            ("fetch.distance_happiness", self.fetch.distance_happiness),
+            ("fetch.streak_bonus", self.fetch.streak_bonus),
+            ("fetch.max_happiness", self.fetch.max_happiness),
+            ("fetch.play_happiness", self.fetch.play_happiness),
+            ("fetch.play_energy", self.fetch.play_energy),
+            ("items.spawn_min_distance", self.items.spawn_min_distance),
+            ("items.spawn_max_distance", self.items.spawn_max_distance),
+        ]
+        .map(|(key, value)| (key.to_string(), value)));
+        for (key, value) in non_negative {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ConfigError::Invalid(format!("{} must not be negative (got {})", key, value)));
+            }
+        }
+        let friction = self.movement.friction;
+        if !friction.is_finite() || friction <= 0.0 || friction > 1.0 {
+            return Err(ConfigError::Invalid(format!(
+                "movement.friction must be between 0 and 1 (got {})",
+                friction
+            )));
+        }
+        if self.bugs.threat_radius < self.bugs.contact_radius {
+            return Err(ConfigError::Invalid(format!(
+                "bugs.threat_radius ({}) must not be smaller than bugs.contact_radius ({})",
+                self.bugs.threat_radius, self.bugs.contact_radius
+            )));
+        }
+        // The pet has to grow up in order.
+        let growth = &self.growth;
+        let ages = [
+            ("growth.hatch_age", growth.hatch_age),
+            ("growth.child_age", growth.child_age),
+            ("growth.adult_age", growth.adult_age),
+            ("growth.elder_age", growth.elder_age),
+        ];
+        for pair in ages.windows(2) {
+            let ((earlier, earlier_age), (later, later_age)) = (pair[0], pair[1]);
+            if !later_age.is_finite() || later_age < earlier_age {
+                return Err(ConfigError::Invalid(format!(
+                    "{} ({}) must not be smaller than {} ({})",
+                    later, later_age, earlier, earlier_age
+                )));
+            }
+        }
+        if growth.radiant_care < growth.gloomy_care {
+            return Err(ConfigError::Invalid(format!(
+                "growth.radiant_care ({}) must not be smaller than growth.gloomy_care ({})",
+                growth.radiant_care, growth.gloomy_care
+            )));
+        }
+        if self.mood.calm_radius < self.mood.scare_radius {
+            return Err(ConfigError::Invalid(format!(
+                "mood.calm_radius ({}) must not be smaller than mood.scare_radius ({})",
+                self.mood.calm_radius, self.mood.scare_radius
+            )));
+        }
+        let rain_friction = self.weather.rain_friction;
+        if !rain_friction.is_finite() || rain_friction <= 0.0 || rain_friction > 1.0 {
+            return Err(ConfigError::Invalid(format!(
+                "weather.rain_friction must be between 0 and 1 (got {})",
+                rain_friction
+            )));
+        }
+        if self.weather.max_duration < self.weather.min_duration {
+            return Err(ConfigError::Invalid(format!(
+                "weather.max_duration ({}) must not be smaller than weather.min_duration ({})",
+                self.weather.max_duration, self.weather.min_duration
+            )));
+        }
+        if self.weather.weights.total() == 0 {
+            return Err(ConfigError::Invalid("weather.weights must give at least one kind of weather a weight above 0".to_string()));
+        }
+        let start_hour = self.day.start_hour;
+        if !start_hour.is_finite() || !(0.0..24.0).contains(&start_hour) {
+            return Err(ConfigError::Invalid(format!("day.start_hour must be from 0 up to 24 (got {})", start_hour)));
+        }
+        if !self.fetch.max_speed.is_finite() || self.fetch.max_speed < self.fetch.min_speed {
+            return Err(ConfigError::Invalid(format!(
+                "fetch.max_speed ({}) must not be smaller than fetch.min_speed ({})",
+                self.fetch.max_speed, self.fetch.min_speed
+            )));
+        }
+        if self.items.max_stack == 0 {
+            return Err(ConfigError::Invalid("items.max_stack must be at least 1".to_string()));
+        }
+        if self.items.spawn_max_distance < self.items.spawn_min_distance {
+            return Err(ConfigError::Invalid(format!(
+                "items.spawn_max_distance ({}) must not be smaller than items.spawn_min_distance ({})",
+                self.items.spawn_max_distance, self.items.spawn_min_distance
+            )));
+        }
+        for kind in ItemKind::ALL {
+            let item = self.items.get(kind);
+            for (field, value) in [("nutrition", item.nutrition), ("happiness", item.happiness), ("energy", item.energy)] {
+                if !value.is_finite() {
+                    return Err(ConfigError::Invalid(format!("items.{}.{} must be a number (got {})", kind.name(), field, value)));
+                }
+            }
+        }
+        let offline = [("offline.hunger_cap", self.offline.hunger_cap), ("offline.happiness_floor", self.offline.happiness_floor)];
+        for (key, value) in offline {
+            if !(0.0..=100.0).contains(&value) {
+                return Err(ConfigError::Invalid(format!("{} must be from 0 to 100 (got {})", key, value)));
+            }
+        }
+        // A pet has to be of some species, and every species needs its own name to be picked by.
+        if self.species.0.is_empty() {
+            return Err(ConfigError::Invalid("species must list at least one species".to_string()));
+        }
+        for (index, species) in self.species.0.iter().enumerate() {
+            if species.name.trim().is_empty() || self.species.0[..index].iter().any(|other| other.name == species.name) {
+                return Err(ConfigError::Invalid(format!("species names must be unique and not empty (got \"{}\")", species.name)));
+            }
+            let stats = [("hunger", species.hunger), ("happiness", species.happiness), ("energy", species.energy)];
+            for (field, value) in stats {
+                if !(0.0..=100.0).contains(&value) {
+                    return Err(ConfigError::Invalid(format!("species.{}.{} must be from 0 to 100 (got {})", species.name, field, value)));
+                }
+            }
+            let colors = species.parts.iter().map(|part| match part {
+                Part::Sphere { color, .. } | Part::Cube { color, .. } => color,
+            });
+            if !std::iter::once(&species.color).chain(colors).flatten().all(|c| (0.0..=1.0).contains(c)) {
+                return Err(ConfigError::Invalid(format!("species.{} colours must be from 0 to 1", species.name)));
+            }
+        }
+        // A difficulty that spawns bugs needs at least one kind of bug to pick from.
+        for difficulty in Difficulty::ALL {
+            let preset = self.difficulty.get(difficulty);
+            if preset.spawn_count > 0 && preset.weights.total() == 0 {
+                return Err(ConfigError::Invalid(format!(
+                    "difficulty.{}.weights must give at least one bug kind a weight above 0",
+                    difficulty.name().to_lowercase()
+                )));
+            }
+        }
+        // Bugs must not be removed the moment they spawn.
+        for difficulty in Difficulty::ALL {
+            let spawn_distance = self.difficulty.get(difficulty).spawn_distance;
+            if self.bugs.despawn_distance <= spawn_distance {
+                return Err(ConfigError::Invalid(format!(
+                    "bugs.despawn_distance ({}) must be larger than difficulty.{}.spawn_distance ({})",
+                    self.bugs.despawn_distance,
+                    difficulty.name().to_lowercase(),
+                    spawn_distance
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Watches the configuration file and reloads it whenever it changes on disk.
+pub struct ConfigWatcher {
+    path: String,
+    // Modification time of the file when it was last loaded.
+    modified: Option<SystemTime>,
+    // Seconds until the file is checked again.
+    check_timer: f32,
+}
+
+impl ConfigWatcher {
+    // How often the file's modification time is checked, in seconds.
+    const CHECK_INTERVAL: f32 = 1.0;
+
+    pub fn new(path: String) -> ConfigWatcher {
+        let modified = modified_time(&path);
+        ConfigWatcher { path, modified, check_timer: ConfigWatcher::CHECK_INTERVAL }
+    }
+
+    // Call once per frame with the frame time.
+    // Returns the freshly loaded configuration (or the error) when the file has changed.
+    pub fn poll(&mut self, dt: f32) -> Option<Result<Config, ConfigError>> {
+        self.check_timer -= dt;
+        if self.check_timer > 0.0 {
+            return None;
+        }
+        self.check_timer = ConfigWatcher::CHECK_INTERVAL;
+
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(Config::load(&self.path))
+    }
+}
+
+// The last modification time of a file, or None if it does not exist.
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,27 @@
+// The in-game clock. A day lasts DayConfig::length seconds of simulated time, and the height of
+// the sun decides how light it is. Bugs grow bolder in the dark, and the renderer follows the sun.
+use std::f32::consts::TAU;
+
+use crate::config::DayConfig;
+
+// The hour of the day, from 0 up to 24, at the given simulated time.
+pub fn hour(time: f64, day: &DayConfig) -> f32 {
+    let days = time / day.length as f64 + day.start_hour as f64 / 24.0;
+    (days.fract() * 24.0) as f32
+}
+
+// Which day it is, starting at day 1.
+pub fn day_number(time: f64, day: &DayConfig) -> u32 {
+    (time / day.length as f64 + day.start_hour as f64 / 24.0) as u32 + 1
+}
+
+// How high the sun stands: -1 at midnight, 0 at 6:00 and 18:00 and 1 at noon.
+pub fn sun_elevation(hour: f32) -> f32 {
+    ((hour - 6.0) / 24.0 * TAU).sin()
+}
+
+// How light it is, from 0 at night to 1 in the day. Dawn and dusk fade between the two
+// while the sun is just below or above the horizon.
+pub fn daylight(hour: f32) -> f32 {
+    ((sun_elevation(hour) + 0.1) / 0.3).clamp(0.0, 1.0)
+}
@@ -0,0 +1,77 @@
+// The fetch mini-game. The pet picks up the ball when told to, the player winds up and throws it,
+// then the pet runs after it by itself, grabs it and brings it back to where it was thrown from.
+// Longer throws and fetches in a row make the pet happier. Taking over the movement keys calls
+// the pet off.
+use serde::{Deserialize, Serialize};
+
+use crate::config::FetchConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum FetchState {
+    // No fetch going on.
+    #[default]
+    Idle,
+    // The ball is flying or rolling, and the pet is running after it.
+    Chasing,
+    // The pet has the ball and is bringing it back. distance is how far from the throwing spot
+    // the pet caught up with it.
+    Returning { distance: f32 },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fetch {
+    pub state: FetchState,
+    // Where the ball was thrown from, which is where it has to be brought back to.
+    pub origin_x: f32,
+    pub origin_z: f32,
+    // Seconds since the ball was thrown.
+    pub elapsed: f32,
+    // Fetches completed in a row.
+    pub streak: u32,
+    // Seconds the throw key has been held so far.
+    pub charge: f32,
+    // The throw key picked the ball up and has not been let go since.
+    #[serde(default)]
+    pub grabbed: bool,
+}
+
+impl Fetch {
+    // true while the pet is chasing the ball or bringing it back.
+    pub fn active(&self) -> bool {
+        self.state != FetchState::Idle
+    }
+
+    // How hard the ball would be thrown right now, from 0 to 1.
+    pub fn power(&self, config: &FetchConfig) -> f32 {
+        (self.charge / config.charge_time).min(1.0)
+    }
+
+    // The speed of a ball thrown with the given power.
+    pub fn throw_speed(power: f32, config: &FetchConfig) -> f32 {
+        config.min_speed + (config.max_speed - config.min_speed) * power
+    }
+
+    // Start a fetch for a ball thrown from the given spot.
+    pub fn throw(&mut self, x: f32, z: f32) {
+        self.state = FetchState::Chasing;
+        self.origin_x = x;
+        self.origin_z = z;
+        self.elapsed = 0.0;
+        self.charge = 0.0;
+    }
+
+    // Stop the fetch without a reward. The streak is lost.
+    pub fn give_up(&mut self) {
+        self.state = FetchState::Idle;
+        self.streak = 0;
+    }
+
+    // Finish the fetch and return the happiness it earned: more for a longer throw, and more for
+    // every fetch in a row before this one.
+    pub fn complete(&mut self, distance: f32, config: &FetchConfig) -> f32 {
+        let bonus = 1.0 + config.streak_bonus * self.streak as f32;
+        self.state = FetchState::Idle;
+        self.streak += 1;
+        (distance * config.distance_happiness * bonus).min(config.max_happiness)
+    }
+}
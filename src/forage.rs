@@ -0,0 +1,60 @@
+// Which berries have been picked and which puddles drunk dry, and when they grow back.
+// Chunks themselves are thrown away when the pet leaves them and generated again on return,
+// so what happened to their features is kept here instead, per chunk, and saved with the world.
+use serde::{Deserialize, Serialize};
+
+// A picked berry or an emptied puddle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Taken {
+    // The index of the feature in its chunk's feature list.
+    feature: usize,
+    // The simulated time at which it has grown back.
+    ready_at: f64,
+}
+
+// Everything taken from one chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChunkForage {
+    cx: i32,
+    cz: i32,
+    taken: Vec<Taken>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Forage {
+    // Only chunks where something is still growing back are listed.
+    chunks: Vec<ChunkForage>,
+}
+
+impl Forage {
+    // true if the feature can be picked or drunk from at the given time.
+    pub fn is_ripe(&self, cx: i32, cz: i32, feature: usize, now: f64) -> bool {
+        self.chunks
+            .iter()
+            .find(|chunk| chunk.cx == cx && chunk.cz == cz)
+            .and_then(|chunk| chunk.taken.iter().find(|taken| taken.feature == feature))
+            .is_none_or(|taken| taken.ready_at <= now)
+    }
+
+    // Remember that a feature was used up and grows back at ready_at.
+    pub fn take(&mut self, cx: i32, cz: i32, feature: usize, ready_at: f64) {
+        let index = match self.chunks.iter().position(|chunk| chunk.cx == cx && chunk.cz == cz) {
+            Some(index) => index,
+            None => {
+                self.chunks.push(ChunkForage { cx, cz, taken: Vec::new() });
+                self.chunks.len() - 1
+            }
+        };
+        let taken = &mut self.chunks[index].taken;
+        taken.retain(|taken| taken.feature != feature);
+        taken.push(Taken { feature, ready_at });
+    }
+
+    // Forget everything that has grown back, so the list only holds what is still missing.
+    pub fn regrow(&mut self, now: f64) {
+        for chunk in self.chunks.iter_mut() {
+            chunk.taken.retain(|taken| taken.ready_at > now);
+        }
+        self.chunks.retain(|chunk| !chunk.taken.is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+// Gamepad support. Pads are read through gilrs, which works on Linux, Windows and macOS and maps
+// every pad it knows to the same Xbox-style layout, whatever the driver numbers its buttons as.
+// While no pad is plugged in, or where gilrs cannot read pads at all, the sticks read zero and no
+// button is ever pressed, so only the keyboard counts.
+use gilrs::{Axis, EventType, GamepadId, Gilrs};
+use macroquad::prelude::*;
+
+use gaemthesecond::input::InputState;
+
+// How far a stick has to be pushed before it counts, as a fraction of the way.
+const DEAD_ZONE: f32 = 0.2;
+// How far the right stick turns the first person view, in mouse movement per second at full tilt.
+const STICK_LOOK_SPEED: f32 = 0.7;
+
+// The buttons of an Xbox-style pad. Other pads have the same buttons in the same places.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftStick,
+    RightStick,
+}
+
+impl Button {
+    const ALL: [Button; 11] = [
+        Button::A,
+        Button::B,
+        Button::X,
+        Button::Y,
+        Button::LeftBumper,
+        Button::RightBumper,
+        Button::Back,
+        Button::Start,
+        Button::Guide,
+        Button::LeftStick,
+        Button::RightStick,
+    ];
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+
+    // The name gilrs gives the button, which goes by its place on the pad.
+    fn gilrs(self) -> gilrs::Button {
+        match self {
+            Button::A => gilrs::Button::South,
+            Button::B => gilrs::Button::East,
+            Button::X => gilrs::Button::West,
+            Button::Y => gilrs::Button::North,
+            Button::LeftBumper => gilrs::Button::LeftTrigger,
+            Button::RightBumper => gilrs::Button::RightTrigger,
+            Button::Back => gilrs::Button::Select,
+            Button::Start => gilrs::Button::Start,
+            Button::Guide => gilrs::Button::Mode,
+            Button::LeftStick => gilrs::Button::LeftThumb,
+            Button::RightStick => gilrs::Button::RightThumb,
+        }
+    }
+}
+
+// The pad being played with, if there is one, and the buttons that went down since the last update.
+pub struct Gamepad {
+    // None where gilrs could not be started, which leaves the game to the keyboard.
+    gilrs: Option<Gilrs>,
+    // The pad that is played with: the first one plugged in.
+    active: Option<GamepadId>,
+    // One bit per button that went down since the last update.
+    pressed: u32,
+}
+
+impl Default for Gamepad {
+    fn default() -> Gamepad {
+        Gamepad::new()
+    }
+}
+
+impl Gamepad {
+    // Use the first pad that is plugged in, if any.
+    pub fn new() -> Gamepad {
+        let gilrs = Gilrs::new().ok();
+        let active = gilrs.as_ref().and_then(|gilrs| gilrs.gamepads().next().map(|(id, _)| id));
+        Gamepad { gilrs, active, pressed: 0 }
+    }
+
+    // Whether a pad is plugged in.
+    pub fn connected(&self) -> bool {
+        self.pad().is_some()
+    }
+
+    // Read everything that happened on the pads since the last update. When the pad in use is
+    // pulled out, the next one takes over. Returns Some(connected) when a pad was plugged in or
+    // pulled out.
+    pub fn update(&mut self) -> Option<bool> {
+        self.pressed = 0;
+        let was_connected = self.connected();
+        let Some(gilrs) = &mut self.gilrs else {
+            return None;
+        };
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::Connected if self.active.is_none() => self.active = Some(event.id),
+                EventType::Disconnected if self.active == Some(event.id) => {
+                    self.active = gilrs.gamepads().next().map(|(id, _)| id);
+                }
+                EventType::ButtonPressed(button, _) if self.active == Some(event.id) => {
+                    if let Some(&button) = Button::ALL.iter().find(|b| b.gilrs() == button) {
+                        self.pressed |= button.bit();
+                    }
+                }
+                _ => {}
+            }
+        }
+        (was_connected != self.connected()).then_some(self.connected())
+    }
+
+    // The pad in use, if it is still plugged in.
+    fn pad(&self) -> Option<gilrs::Gamepad<'_>> {
+        self.gilrs.as_ref()?.connected_gamepad(self.active?)
+    }
+
+    // Whether a button is held down.
+    pub fn held(&self, button: Button) -> bool {
+        self.pad().is_some_and(|pad| pad.is_pressed(button.gilrs()))
+    }
+
+    // Whether a button went down since the last update.
+    pub fn pressed(&self, button: Button) -> bool {
+        self.pressed & button.bit() != 0
+    }
+
+    // How far a stick is pushed along its two axes.
+    fn stick(&self, x: Axis, y: Axis) -> Vec2 {
+        self.pad().map_or(Vec2::ZERO, |pad| dead_zone(Vec2::new(pad.value(x), pad.value(y))))
+    }
+
+    // How far the left stick is pushed, right and up, each from -1 to 1.
+    pub fn left_stick(&self) -> Vec2 {
+        self.stick(Axis::LeftStickX, Axis::LeftStickY)
+    }
+
+    // How far the right stick is pushed, right and up, each from -1 to 1.
+    pub fn right_stick(&self) -> Vec2 {
+        self.stick(Axis::RightStickX, Axis::RightStickY)
+    }
+
+    // The direction the d-pad is held in, right and up: -1, 0 or 1 on each axis.
+    pub fn dpad(&self) -> Vec2 {
+        let Some(pad) = self.pad() else {
+            return Vec2::ZERO;
+        };
+        let axis = |minus, plus| pad.is_pressed(plus) as i32 as f32 - pad.is_pressed(minus) as i32 as f32;
+        Vec2::new(
+            axis(gilrs::Button::DPadLeft, gilrs::Button::DPadRight),
+            axis(gilrs::Button::DPadDown, gilrs::Button::DPadUp),
+        )
+    }
+
+    // Add the pad to the input read from the keyboard and mouse. The d-pad works like the arrow
+    // keys, and the right stick looks around in first person like the mouse.
+    pub fn add_to(&self, input: &mut InputState) {
+        let dpad = self.dpad();
+        input.forward |= dpad.y > 0.0;
+        input.back |= dpad.y < 0.0;
+        input.left |= dpad.x < 0.0;
+        input.right |= dpad.x > 0.0;
+        input.sprint |= self.held(Button::LeftBumper);
+        input.stick = self.left_stick();
+        if input.first_person {
+            // The mouse delta runs the other way along x, but pushing the stick up looks up.
+            let stick = self.right_stick();
+            input.look_delta += Vec2::new(-stick.x, stick.y) * STICK_LOOK_SPEED * get_frame_time();
+        }
+        input.feed |= self.pressed(Button::X);
+        input.play |= self.pressed(Button::Y);
+        input.sleep |= self.pressed(Button::B);
+        input.jump |= self.pressed(Button::A);
+        input.dash |= self.pressed(Button::RightBumper);
+    }
+}
+
+// Drop small stick movements, so a stick that does not quite spring back to the middle does not
+// move anything. The rest of the way is stretched to still reach 1 at the edge.
+fn dead_zone(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length < DEAD_ZONE {
+        return Vec2::ZERO;
+    }
+    stick / length * ((length - DEAD_ZONE) / (1.0 - DEAD_ZONE)).min(1.0)
+}
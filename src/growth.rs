@@ -0,0 +1,177 @@
+// How the pet grows up: from an egg through baby and child to adult and finally elder, as its age
+// passes the thresholds in GrowthConfig. Every stage has its own size, speed, stat decay and
+// actions, and when the pet grows up its adult form depends on how well it was looked after.
+use serde::{Deserialize, Serialize};
+
+use crate::config::GrowthConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum LifeStage {
+    // Cannot move or do anything, but its shell keeps the bugs out.
+    #[default]
+    Egg,
+    Baby,
+    Child,
+    Adult,
+    Elder,
+}
+
+// The things the player can ask the pet to do. Not every life stage can do all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Move,
+    Jump,
+    Dash,
+    Feed,
+    Play,
+    Sleep,
+    Pounce,
+    EatBug,
+    Forage,
+}
+
+impl Action {
+    // The verb used in messages, e.g. "too young to pounce".
+    pub fn verb(self) -> &'static str {
+        match self {
+            Action::Move => "move",
+            Action::Jump => "jump",
+            Action::Dash => "dash",
+            Action::Feed => "eat",
+            Action::Play => "play",
+            Action::Sleep => "sleep",
+            Action::Pounce => "pounce",
+            Action::EatBug => "eat bugs",
+            Action::Forage => "forage",
+        }
+    }
+}
+
+impl LifeStage {
+    // The stage of a pet of the given age in seconds.
+    pub fn at(age: f64, growth: &GrowthConfig) -> LifeStage {
+        let age = age as f32;
+        if age >= growth.elder_age {
+            LifeStage::Elder
+        } else if age >= growth.adult_age {
+            LifeStage::Adult
+        } else if age >= growth.child_age {
+            LifeStage::Child
+        } else if age >= growth.hatch_age {
+            LifeStage::Baby
+        } else {
+            LifeStage::Egg
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LifeStage::Egg => "Egg",
+            LifeStage::Baby => "Baby",
+            LifeStage::Child => "Child",
+            LifeStage::Adult => "Adult",
+            LifeStage::Elder => "Elder",
+        }
+    }
+
+    // The size of the pet's body compared to an adult.
+    pub fn size(self) -> f32 {
+        match self {
+            LifeStage::Egg => 0.6,
+            LifeStage::Baby => 0.5,
+            LifeStage::Child => 0.75,
+            LifeStage::Adult => 1.0,
+            LifeStage::Elder => 0.95,
+        }
+    }
+
+    // How fast the pet accelerates compared to an adult.
+    pub fn speed(self) -> f32 {
+        match self {
+            LifeStage::Egg => 0.0,
+            LifeStage::Baby => 0.6,
+            LifeStage::Child => 1.1,
+            LifeStage::Adult => 1.0,
+            LifeStage::Elder => 0.7,
+        }
+    }
+
+    // How fast hunger and happiness drain compared to an adult. Babies need the most care.
+    pub fn decay(self) -> f32 {
+        match self {
+            LifeStage::Egg => 0.3,
+            LifeStage::Baby => 1.3,
+            LifeStage::Child => 1.1,
+            LifeStage::Adult => 1.0,
+            LifeStage::Elder => 1.2,
+        }
+    }
+
+    // Whether a pet of this stage can do an action. Eggs cannot do anything, babies are too small
+    // to hunt bugs, and elders are too frail to pounce or dash.
+    pub fn allows(self, action: Action) -> bool {
+        match self {
+            LifeStage::Egg => false,
+            LifeStage::Baby => !matches!(action, Action::Pounce | Action::EatBug),
+            LifeStage::Child | LifeStage::Adult => true,
+            LifeStage::Elder => !matches!(action, Action::Pounce | Action::Dash),
+        }
+    }
+}
+
+// The form the pet grows into when it becomes an adult, chosen by how well it was cared for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Form {
+    // Very well cared for: its stats drain slower and it is quick on its feet.
+    Radiant,
+    // Reasonably cared for: tough, bug bites hurt it less.
+    Sturdy,
+    // Neglected: its happiness drains faster.
+    Gloomy,
+}
+
+impl Form {
+    // The form for an average care score from 0 to 100.
+    pub fn from_care(care: f32, growth: &GrowthConfig) -> Form {
+        if care >= growth.radiant_care {
+            Form::Radiant
+        } else if care >= growth.gloomy_care {
+            Form::Sturdy
+        } else {
+            Form::Gloomy
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Form::Radiant => "radiant",
+            Form::Sturdy => "sturdy",
+            Form::Gloomy => "gloomy",
+        }
+    }
+
+    // Factor on how fast hunger and happiness drain.
+    pub fn decay(self) -> f32 {
+        match self {
+            Form::Radiant => 0.85,
+            Form::Sturdy => 1.0,
+            Form::Gloomy => 1.25,
+        }
+    }
+
+    // Factor on the pet's acceleration.
+    pub fn speed(self) -> f32 {
+        match self {
+            Form::Radiant => 1.1,
+            Form::Sturdy | Form::Gloomy => 1.0,
+        }
+    }
+
+    // Factor on the damage of bug bites.
+    pub fn damage(self) -> f32 {
+        match self {
+            Form::Sturdy => 0.75,
+            Form::Radiant | Form::Gloomy => 1.0,
+        }
+    }
+}
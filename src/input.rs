@@ -0,0 +1,113 @@
+// The InputState struct is a plain snapshot of everything the player asked for during one tick.
+// The simulation only ever looks at this struct, never at the keyboard or mouse directly,
+// which means it can be driven by tests or a headless runner just as well as by macroquad.
+use macroquad::prelude::*;
+
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    // Movement keys currently held down (W/Up, S/Down, A/Left, D/Right).
+    pub forward: bool,
+    pub back: bool,
+    pub left: bool,
+    pub right: bool,
+    // The sprint key is held down (Shift).
+    pub sprint: bool,
+    // How far the gamepad's movement stick is pushed: x to the right and y forward, each from -1 to 1.
+    // It works like the movement keys, but the pet speeds up only as much as the stick is pushed.
+    pub stick: Vec2,
+    // Mouse movement since the last tick, used for looking around in first person.
+    pub look_delta: Vec2,
+    // true when the camera is in first person, which turns A/D into strafing.
+    pub first_person: bool,
+    // One-shot actions: these are only true on the tick the key was pressed.
+    pub feed: bool,
+    pub play: bool,
+    pub sleep: bool,
+    pub pounce: bool,
+    pub eat: bool,
+    pub gather: bool,
+    // Jump, or jump again in mid-air (Space).
+    pub jump: bool,
+    // A burst of speed in the direction the pet is going (Q).
+    pub dash: bool,
+    // Select the next hotbar slot, and use the selected item.
+    pub next_item: bool,
+    pub use_item: bool,
+    // The throw key is held down (T). Running into the ball with it held picks the ball up, and
+    // holding it again winds up a throw that is thrown when it is let go.
+    pub throw: bool,
+}
+
+impl InputState {
+    // Read the current keyboard and mouse state from macroquad. The game adds the gamepad on top.
+    pub fn poll(first_person: bool) -> InputState {
+        InputState {
+            forward: is_key_down(KeyCode::Up) || is_key_down(KeyCode::W),
+            back: is_key_down(KeyCode::Down) || is_key_down(KeyCode::S),
+            left: is_key_down(KeyCode::Left) || is_key_down(KeyCode::A),
+            right: is_key_down(KeyCode::Right) || is_key_down(KeyCode::D),
+            sprint: is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift),
+            stick: Vec2::ZERO,
+            // The mouse is only used for looking in first person.
+            look_delta: if first_person { mouse_delta_position() } else { Vec2::ZERO },
+            first_person,
+            feed: is_key_pressed(KeyCode::Key1),
+            play: is_key_pressed(KeyCode::Key2),
+            sleep: is_key_pressed(KeyCode::Key3),
+            pounce: is_key_pressed(KeyCode::F),
+            eat: is_key_pressed(KeyCode::E),
+            gather: is_key_pressed(KeyCode::G),
+            jump: is_key_pressed(KeyCode::Space),
+            dash: is_key_pressed(KeyCode::Q),
+            next_item: is_key_pressed(KeyCode::Tab),
+            use_item: is_key_pressed(KeyCode::C),
+            throw: is_key_down(KeyCode::T),
+        }
+    }
+
+    // Returns true if any movement key is being held or the stick is pushed.
+    pub fn is_moving(&self) -> bool {
+        self.forward || self.back || self.left || self.right || self.stick != Vec2::ZERO
+    }
+
+    // Fold a freshly polled frame into this input. Held keys are replaced by the latest state,
+    // while presses and mouse movement are kept until a tick consumes them. This way nothing is
+    // lost on fast monitors where a frame can end before a full tick has passed.
+    pub fn accumulate(&mut self, latest: InputState) {
+        self.forward = latest.forward;
+        self.back = latest.back;
+        self.left = latest.left;
+        self.right = latest.right;
+        self.sprint = latest.sprint;
+        self.stick = latest.stick;
+        self.first_person = latest.first_person;
+        self.throw = latest.throw;
+        self.look_delta += latest.look_delta;
+        self.feed |= latest.feed;
+        self.play |= latest.play;
+        self.sleep |= latest.sleep;
+        self.pounce |= latest.pounce;
+        self.eat |= latest.eat;
+        self.gather |= latest.gather;
+        self.jump |= latest.jump;
+        self.dash |= latest.dash;
+        self.next_item |= latest.next_item;
+        self.use_item |= latest.use_item;
+    }
+
+    // After a tick has consumed them, clear the one-shot parts of the input so that running
+    // several fixed ticks in one frame does not repeat a key press or mouse look.
+    pub fn clear_one_shots(&mut self) {
+        self.look_delta = Vec2::ZERO;
+        self.feed = false;
+        self.play = false;
+        self.sleep = false;
+        self.pounce = false;
+        self.eat = false;
+        self.gather = false;
+        self.jump = false;
+        self.dash = false;
+        self.next_item = false;
+        self.use_item = false;
+    }
+}
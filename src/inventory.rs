@@ -0,0 +1,143 @@
+// The things the pet carries around, and the items lying in the world waiting to be picked up.
+// What each item does is not decided here but in the [items] section of the configuration.
+use serde::{Deserialize, Serialize};
+
+// What an item is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemCategory {
+    // Eaten by the Feed action and used up.
+    Food,
+    // Played with by the Play action. Toys are kept after playing.
+    Toy,
+    // Taken to heal, and used up.
+    Medicine,
+}
+
+// The kinds of items that can be carried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemKind {
+    // A red berry picked from a flower.
+    Berry,
+    // A big, filling apple.
+    Apple,
+    // A mushroom that is not very filling but gives some energy.
+    Mushroom,
+    // A stick to chase around.
+    Stick,
+    // A feather to pounce at.
+    Feather,
+    // A bitter herb that heals the pet.
+    Medicine,
+}
+
+impl ItemKind {
+    // Every kind of item, in the order used by the spawn weights.
+    pub const ALL: [ItemKind; 6] =
+        [ItemKind::Berry, ItemKind::Apple, ItemKind::Mushroom, ItemKind::Stick, ItemKind::Feather, ItemKind::Medicine];
+
+    // The name shown in messages and on the hotbar.
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemKind::Berry => "berry",
+            ItemKind::Apple => "apple",
+            ItemKind::Mushroom => "mushroom",
+            ItemKind::Stick => "stick",
+            ItemKind::Feather => "feather",
+            ItemKind::Medicine => "medicine",
+        }
+    }
+
+    pub fn category(self) -> ItemCategory {
+        match self {
+            ItemKind::Berry | ItemKind::Apple | ItemKind::Mushroom => ItemCategory::Food,
+            ItemKind::Stick | ItemKind::Feather => ItemCategory::Toy,
+            ItemKind::Medicine => ItemCategory::Medicine,
+        }
+    }
+}
+
+// A number of items of the same kind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub kind: ItemKind,
+    pub count: u32,
+}
+
+// An item lying on the ground, picked up as soon as the pet walks over it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundItem {
+    pub kind: ItemKind,
+    pub x: f32,
+    pub z: f32,
+}
+
+// The pet's inventory: one stack per kind of item, in the order they were first picked up.
+// The stacks are shown as the hotbar, with one of them selected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Inventory {
+    pub stacks: Vec<ItemStack>,
+    // Index of the selected stack. Inventories saved before the hotbar existed select the first one.
+    #[serde(default)]
+    pub selected: usize,
+}
+
+impl Inventory {
+    // How many items of the given kind are carried.
+    pub fn count(&self, kind: ItemKind) -> u32 {
+        self.stacks.iter().find(|stack| stack.kind == kind).map_or(0, |stack| stack.count)
+    }
+
+    // Put items into the inventory, on top of the stack of their kind, without letting the stack
+    // grow beyond max_stack. Returns how many items fit.
+    pub fn add(&mut self, kind: ItemKind, count: u32, max_stack: u32) -> u32 {
+        let index = match self.stacks.iter().position(|stack| stack.kind == kind) {
+            Some(index) => index,
+            None => {
+                self.stacks.push(ItemStack { kind, count: 0 });
+                self.stacks.len() - 1
+            }
+        };
+        let stack = &mut self.stacks[index];
+        let added = count.min(max_stack.saturating_sub(stack.count));
+        stack.count += added;
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        added
+    }
+
+    // Take one item of the given kind out. Returns false if there is none.
+    // Empty stacks are removed, keeping the same item selected where possible.
+    pub fn take(&mut self, kind: ItemKind) -> bool {
+        let Some(index) = self.stacks.iter().position(|stack| stack.kind == kind) else {
+            return false;
+        };
+        self.stacks[index].count -= 1;
+        if self.stacks[index].count == 0 {
+            self.stacks.remove(index);
+            if self.selected > index || self.selected >= self.stacks.len() {
+                self.selected = self.selected.saturating_sub(1);
+            }
+        }
+        true
+    }
+
+    // The kind of the selected stack, if the inventory is not empty.
+    pub fn selected(&self) -> Option<ItemKind> {
+        self.stacks.get(self.selected).map(|stack| stack.kind)
+    }
+
+    // Move the selection to the next stack, wrapping around at the end of the hotbar.
+    pub fn select_next(&mut self) {
+        if !self.stacks.is_empty() {
+            self.selected = (self.selected + 1) % self.stacks.len();
+        }
+    }
+
+    // The item of a category to use: the selected one if it fits, otherwise the first one carried.
+    pub fn pick(&self, category: ItemCategory) -> Option<ItemKind> {
+        self.selected()
+            .filter(|kind| kind.category() == category)
+            .or_else(|| self.stacks.iter().map(|stack| stack.kind).find(|kind| kind.category() == category))
+    }
+}
@@ -0,0 +1,24 @@
+// The headless side of the game: the simulation and everything it needs.
+// Nothing in here draws or opens a window, so it can be driven by the game in main.rs,
+// by benchmarks, or by any other tool that wants to step a World.
+pub mod ball;
+pub mod bug;
+pub mod chunks;
+pub mod config;
+pub mod daycycle;
+pub mod fetch;
+pub mod forage;
+pub mod growth;
+pub mod input;
+pub mod inventory;
+pub mod mood;
+pub mod offline;
+pub mod pet;
+pub mod replay;
+pub mod rng;
+pub mod save;
+pub mod spatial;
+pub mod species;
+pub mod terrain;
+pub mod weather;
+pub mod world;
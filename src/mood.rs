@@ -0,0 +1,95 @@
+// The pet's mood, worked out from its stats and the bugs around it.
+// Every mood has a condition to enter it and a looser one to stay in it (by MoodConfig::margin for
+// the stats, or out to calm_radius for bugs), so a stat wobbling around a threshold does not make
+// the mood flicker back and forth.
+use serde::{Deserialize, Serialize};
+
+use crate::config::MoodConfig;
+use crate::pet::Pet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Mood {
+    // Nothing in particular is wrong.
+    #[default]
+    Content,
+    // Unhappy and listless.
+    Bored,
+    // A bug is close.
+    Scared,
+    // Hungry and cross about it.
+    Grumpy,
+    // Happy and full of energy.
+    Playful,
+    // Low on energy, or asleep.
+    Sleepy,
+}
+
+impl Mood {
+    // The moods in order of priority: when several apply, the first one wins.
+    const PRIORITY: [Mood; 5] = [Mood::Scared, Mood::Sleepy, Mood::Grumpy, Mood::Playful, Mood::Bored];
+
+    // The name shown on the HUD.
+    pub fn name(self) -> &'static str {
+        match self {
+            Mood::Content => "Content",
+            Mood::Bored => "Bored",
+            Mood::Scared => "Scared",
+            Mood::Grumpy => "Grumpy",
+            Mood::Playful => "Playful",
+            Mood::Sleepy => "Sleepy",
+        }
+    }
+
+    // The message shown when the pet gets into this mood.
+    pub fn message(self, name: &str) -> String {
+        match self {
+            Mood::Content => format!("{} feels content.", name),
+            Mood::Bored => format!("{} is bored. Play with it!", name),
+            Mood::Scared => format!("{} is scared of the bugs!", name),
+            Mood::Grumpy => format!("{} is getting grumpy. Feed it!", name),
+            Mood::Playful => format!("{} is feeling playful!", name),
+            Mood::Sleepy => format!("{} is getting sleepy...", name),
+        }
+    }
+
+    // How quickly the pet reacts to the movement keys in this mood, as a factor on its
+    // acceleration and turning speed.
+    pub fn responsiveness(self) -> f32 {
+        match self {
+            Mood::Content => 1.0,
+            Mood::Bored => 0.8,
+            Mood::Scared => 1.3,
+            Mood::Grumpy => 0.85,
+            Mood::Playful => 1.2,
+            Mood::Sleepy => 0.6,
+        }
+    }
+
+    // Whether the pet is in this mood. Staying in the current mood uses the looser condition.
+    // nearest_bug is the distance to the closest bug within calm_radius, if there is one.
+    fn holds(self, pet: &Pet, nearest_bug: Option<f32>, config: &MoodConfig, staying: bool) -> bool {
+        let margin = if staying { config.margin } else { 0.0 };
+        match self {
+            Mood::Content => true,
+            // A sleeping pet does not notice the bugs.
+            Mood::Scared => {
+                let radius = if staying { config.calm_radius } else { config.scare_radius };
+                !pet.asleep && nearest_bug.is_some_and(|dist| dist < radius)
+            }
+            Mood::Sleepy => pet.asleep || pet.energy < config.sleepy_energy + margin,
+            Mood::Grumpy => pet.hunger > config.grumpy_hunger - margin,
+            Mood::Playful => {
+                pet.happiness > config.playful_happiness - margin && pet.energy > config.playful_energy - margin
+            }
+            Mood::Bored => pet.happiness < config.bored_happiness + margin,
+        }
+    }
+
+    // The mood the pet is in after this one, given its stats and the bugs around it.
+    pub fn next(self, pet: &Pet, nearest_bug: Option<f32>, config: &MoodConfig) -> Mood {
+        Mood::PRIORITY
+            .into_iter()
+            .find(|&mood| mood.holds(pet, nearest_bug, config, mood == self))
+            .unwrap_or(Mood::Content)
+    }
+}
@@ -0,0 +1,59 @@
+// Offline aging: when a saved pet is continued, it catches up on the time the game was closed.
+use crate::config::{OfflineConfig, SpeciesConfig};
+use crate::pet::{IDLE_ENERGY_PER_SECOND, Pet};
+
+// A summary of what happened to the pet while the game was closed.
+#[derive(Debug, Clone)]
+pub struct AwayReport {
+    // Real seconds since the game was saved.
+    pub elapsed: f64,
+    // Stats before and after the offline decay.
+    pub hunger: (f32, f32),
+    pub happiness: (f32, f32),
+    pub energy: (f32, f32),
+}
+
+impl AwayReport {
+    // Format the elapsed time like "2h 5m" or "42s" for the summary screen.
+    pub fn elapsed_text(&self) -> String {
+        let secs = self.elapsed as u64;
+        let (days, hours, mins) = (secs / 86400, (secs / 3600) % 24, (secs / 60) % 60);
+        if days > 0 {
+            format!("{}d {}h", days, hours)
+        } else if hours > 0 {
+            format!("{}h {}m", hours, mins)
+        } else if mins > 0 {
+            format!("{}m {}s", mins, secs % 60)
+        } else {
+            format!("{}s", secs)
+        }
+    }
+}
+
+// Apply the stat changes for `elapsed` real seconds of absence to a pet of the given species.
+// The per-second rates are the same as in Pet::update, but the limits from the configuration make
+// sure the pet is hungry and sad after a long break instead of dead.
+// The pet is resting while the game is closed, so it recovers energy like an idle pet.
+pub fn apply_offline_decay(pet: &mut Pet, elapsed: f64, species: &SpeciesConfig, settings: &OfflineConfig) -> AwayReport {
+    let before = (pet.hunger, pet.happiness, pet.energy);
+    let (hunger_rate, happiness_rate) = pet.decay_rates(species);
+    // A clock that moved backwards counts as no time passing.
+    let secs = elapsed.clamp(0.0, settings.max_elapsed as f64) as f32;
+
+    // Offline decay may not go past the caps, but it never undoes what already happened either:
+    // a pet that was saved hungrier than the cap stays exactly as hungry.
+    if pet.hunger < settings.hunger_cap {
+        pet.hunger = (pet.hunger + hunger_rate * secs).min(settings.hunger_cap);
+    }
+    if pet.happiness > settings.happiness_floor {
+        pet.happiness = (pet.happiness - happiness_rate * secs).max(settings.happiness_floor);
+    }
+    pet.energy = (pet.energy + IDLE_ENERGY_PER_SECOND * secs).min(100.0);
+
+    AwayReport {
+        elapsed: elapsed.max(0.0),
+        hunger: (before.0, pet.hunger),
+        happiness: (before.1, pet.happiness),
+        energy: (before.2, pet.energy),
+    }
+}
@@ -0,0 +1,591 @@
+use std::f32::consts::FRAC_PI_2;
+
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, GrowthConfig, HealthConfig, ItemConfig, JumpConfig, SpeciesConfig, SprintConfig};
+use crate::growth::{Form, LifeStage};
+use crate::input::InputState;
+use crate::inventory::{Inventory, ItemKind};
+use crate::mood::Mood;
+use crate::terrain::{self, Collider};
+use crate::weather::WeatherEffects;
+
+// How the stats change for every second that passes, before the pet's species, stage and form are
+// taken into account (see Pet::decay_rates).
+pub const HUNGER_PER_SECOND: f32 = 1.0;
+pub const HAPPINESS_LOSS_PER_SECOND: f32 = 0.25;
+pub const IDLE_ENERGY_PER_SECOND: f32 = 1.0;
+pub const MOVING_ENERGY_LOSS_PER_SECOND: f32 = 0.5;
+
+// The radius of a grown-up pet's round body.
+pub const PET_RADIUS: f32 = 1.0;
+
+// Fraction of the landing squash left after every 60Hz frame.
+const SQUASH_RECOVERY: f32 = 0.85;
+
+// What the pet died of, shown on the game over screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeathCause {
+    // Hunger reached 100.
+    Starvation,
+    // Energy ran out.
+    Exhaustion,
+    // Bug bites took all of its health.
+    Bugs,
+}
+
+impl DeathCause {
+    // How the game over screen describes the death.
+    pub fn description(self) -> &'static str {
+        match self {
+            DeathCause::Starvation => "starved",
+            DeathCause::Exhaustion => "collapsed from exhaustion",
+            DeathCause::Bugs => "was eaten by bugs",
+        }
+    }
+}
+
+// The Pet struct represents the main entity in our simulation.
+// It stores all attributes related to its state, position, and movement.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pet {
+    // The user-defined name of the pet.
+    pub name: String,
+    // hunger: A value from 0.0 to 100.0. Higher means the pet is hungrier.
+    pub hunger: f32,
+    // happiness: A value from 0.0 to 100.0. Higher means the pet is happier.
+    pub happiness: f32,
+    // energy: A value from 0.0 to 100.0. Higher means the pet has more energy.
+    pub energy: f32,
+    // health: A value from 0.0 to 100.0. Bug bites take it away; the pet dies when it reaches 0.
+    #[serde(default = "full_health")]
+    pub health: f32,
+    // Seconds left during which bug bites do no harm, right after the pet was bitten.
+    #[serde(default)]
+    pub invulnerable: f32,
+    // Boolean flag to track if the pet is currently "alive" (game over state).
+    pub is_alive: bool,
+    // Seconds of simulated time accumulated since the stats were last degraded.
+    pub stat_timer: f32,
+    // x: World coordinate for horizontal position.
+    pub x: f32,
+    // y: World coordinate for vertical position: the height of the ground (or water) below the pet.
+    pub y: f32,
+    // z: World coordinate for depth position (forward/backward).
+    pub z: f32,
+    // height: How far above the ground the pet is while jumping.
+    #[serde(default)]
+    pub height: f32,
+    // vy: Vertical velocity while jumping, in units per 60Hz frame.
+    #[serde(default)]
+    pub vy: f32,
+    // jumps: How many times the pet jumped since it last stood on the ground: 1 in a jump, 2 after a double jump.
+    #[serde(default)]
+    pub jumps: u32,
+    // sprinting: The pet is running at full tilt this tick.
+    #[serde(default)]
+    pub sprinting: bool,
+    // dash_cooldown: Seconds left until the pet can dash again.
+    #[serde(default)]
+    pub dash_cooldown: f32,
+    // squash: How flat the pet is from its last landing, from 0 (not at all) to 1, springing back over time.
+    #[serde(default)]
+    pub squash: f32,
+    // vx: Velocity along the X-axis (horizontal), in units per 60Hz frame.
+    pub vx: f32,
+    // vz: Velocity along the Z-axis (depth), in units per 60Hz frame.
+    pub vz: f32,
+    // rotation_v: Tracks vertical rotation to simulate the pet rolling forward or backward.
+    pub rotation_v: f32,
+    // pitch: Vertical look angle for the FirstPerson camera (looking up/down).
+    pub pitch: f32,
+    // yaw: Horizontal rotation angle for movement and camera direction.
+    pub yaw: f32,
+    // species: The name of the pet's species in the configuration, which decides its model,
+    // how fast its stats drain, how fast it moves and its special ability.
+    #[serde(default = "first_species")]
+    pub species: String,
+    // stage: How far the pet has grown up, following its age.
+    #[serde(default)]
+    pub stage: LifeStage,
+    // form: What the pet grew into when it became an adult.
+    #[serde(default)]
+    pub form: Option<Form>,
+    // care_total: The care scores summed over every second until the pet grew up, and the
+    // number of seconds, so the average decides its form.
+    #[serde(default)]
+    pub care_total: f32,
+    #[serde(default)]
+    pub care_seconds: f32,
+    // mood: How the pet feels, worked out by the world every tick. It changes how quickly the pet moves.
+    #[serde(default)]
+    pub mood: Mood,
+    // asleep: The pet is sleeping. It regains energy until it is rested, woken up, or bitten.
+    #[serde(default)]
+    pub asleep: bool,
+    // is_stationary: Boolean flag to track if the pet is currently not moving.
+    // This is required for actions like feeding, playing, and sleeping.
+    pub is_stationary: bool,
+    // The simulated time (seconds) when this pet was created/started its current life.
+    pub start_time: f64,
+    // The simulated time when this pet passed away.
+    pub death_time: Option<f64>,
+    // What the pet died of.
+    #[serde(default)]
+    pub death_cause: Option<DeathCause>,
+    // The food and other items the pet is carrying.
+    #[serde(default)]
+    pub inventory: Inventory,
+}
+
+// Pets saved before health existed start with full health.
+fn full_health() -> f32 {
+    100.0
+}
+
+// Pets saved before there were species are blobs, the original round pet.
+fn first_species() -> String {
+    "blob".to_string()
+}
+
+// Implement methods for the Pet struct.
+impl Pet {
+    // Constructor to initialize a new Pet with a name and the starting stats of its species.
+    pub fn new(name: String, species: &SpeciesConfig) -> Pet {
+        Pet {
+            name,
+            hunger: species.hunger,
+            happiness: species.happiness,
+            energy: species.energy,
+            // Health starts full.
+            health: 100.0,
+            invulnerable: 0.0,
+            // Start the pet in an alive state.
+            is_alive: true,
+            // The stat degradation timer starts empty.
+            stat_timer: 0.0,
+            // Start at the world origin (0,0,0).
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            // No initial movement or rotation, and on the ground.
+            height: 0.0,
+            vx: 0.0,
+            vy: 0.0,
+            vz: 0.0,
+            jumps: 0,
+            squash: 0.0,
+            sprinting: false,
+            dash_cooldown: 0.0,
+            rotation_v: 0.0,
+            pitch: 0.0,
+            yaw: 0.0,
+            is_stationary: true,
+            species: species.name.clone(),
+            // Every pet starts out as an egg.
+            stage: LifeStage::Egg,
+            form: None,
+            care_total: 0.0,
+            care_seconds: 0.0,
+            mood: Mood::Content,
+            asleep: false,
+            // The pet's life starts at the beginning of the simulation.
+            start_time: 0.0,
+            // Initially, the pet hasn't died.
+            death_time: None,
+            death_cause: None,
+            // The pockets start empty; the world hands out the starting food.
+            inventory: Inventory::default(),
+        }
+    }
+
+    // How much hunger the pet gains and happiness it loses every second, which depends on its
+    // species, stage and form. Shared with the offline decay, so the pet ages at the same pace
+    // while the game is closed.
+    pub fn decay_rates(&self, species: &SpeciesConfig) -> (f32, f32) {
+        let decay = self.stage.decay() * self.form.map_or(1.0, Form::decay);
+        (HUNGER_PER_SECOND * decay * species.hunger_decay, HAPPINESS_LOSS_PER_SECOND * decay * species.happiness_decay)
+    }
+
+    // Update function to handle input, movement, and stat changes.
+    // dt is the length of this tick in seconds and now is the simulated time at the end of it.
+    // The weather decides how slippery the ground is and how fast the stats drain.
+    pub fn update(&mut self, dt: f32, input: &InputState, now: f64, config: &Config, weather: &WeatherEffects) {
+        let (movement, health, actions) = (&config.movement, &config.health, &config.actions);
+        // If the pet has already passed away, we skip all logic updates.
+        if !self.is_alive {
+            return;
+        }
+        self.grow(now, &config.growth);
+        let species = config.species.get(&self.species);
+        let (hunger_rate, happiness_rate) = self.decay_rates(species);
+
+        // All the movement constants below were tuned for 60 frames per second.
+        // Scaling them by the number of "60Hz frames" in this tick keeps the speed the same at any frame rate.
+        let frames = dt * 60.0;
+
+        // Check if any movement keys are being pressed this tick.
+        // We do this check early so we can use it for stat updates.
+        let is_moving = input.is_moving();
+        // Moving the pet wakes it up.
+        if is_moving {
+            self.asleep = false;
+        }
+
+        // Also check if the pet has significant velocity. A pet in mid-air is not standing still either.
+        let velocity_sq = self.vx * self.vx + self.vz * self.vz;
+        self.is_stationary = !is_moving && velocity_sq < 0.0001 && self.jumps == 0;
+
+        // The invulnerability after a bug bite wears off, and the dash recharges.
+        self.invulnerable = (self.invulnerable - dt).max(0.0);
+        self.dash_cooldown = (self.dash_cooldown - dt).max(0.0);
+
+        // Accumulate simulated time and degrade the stats once for every full second that passed.
+        // This makes the stats decay over time rather than every frame.
+        self.stat_timer += dt;
+        while self.stat_timer >= 1.0 {
+            // Every second, the pet gets hungrier, less happy. Heat makes it hungry faster, and rain makes it sad.
+            self.hunger += hunger_rate * weather.drain;
+            self.happiness -= happiness_rate + weather.happiness_loss;
+
+            // A sleeping pet recovers energy quickly.
+            // Otherwise, if the pet is NOT moving (idle/afk), it gains 1 energy per second.
+            if self.asleep {
+                self.energy += actions.sleep_energy;
+            } else if !is_moving {
+                self.energy += IDLE_ENERGY_PER_SECOND / weather.drain;
+            } else {
+                // Moving consumes energy.
+                self.energy -= MOVING_ENERGY_LOSS_PER_SECOND * species.energy_decay * weather.drain;
+            }
+
+            // A well fed, happy pet slowly heals.
+            if self.hunger < health.regen_max_hunger && self.happiness > health.regen_min_happiness {
+                self.health = (self.health + health.regen_per_second).min(100.0);
+            }
+
+            // Until it grows up, every second counts towards how well the pet was cared for.
+            if self.form.is_none() {
+                self.care_total += (100.0 - self.hunger + self.happiness + self.energy + self.health) / 4.0;
+                self.care_seconds += 1.0;
+            }
+
+            self.stat_timer -= 1.0;
+        }
+        // Once it is fully rested, the pet wakes up by itself.
+        if self.asleep && self.energy >= 100.0 {
+            self.asleep = false;
+        }
+
+        // Acceleration constant: how much velocity is added per 60Hz frame when moving.
+        // A playful or scared pet reacts quickly, a sleepy one sluggishly. Babies and elders are slow,
+        // and an egg cannot move at all.
+        let responsiveness = self.mood.responsiveness() * species.speed * self.stage.speed() * self.form.map_or(1.0, Form::speed);
+        // Sprinting makes the pet speed up faster.
+        let sprint = if input.sprint { config.sprint.accel_factor } else { 1.0 };
+        let accel = movement.accel * responsiveness * sprint;
+        // Rotation speed constant: how fast the pet turns left or right.
+        let rotation_speed = movement.rotation_speed * responsiveness * frames;
+
+        // Track if the pet actually moved this tick (for animation).
+        let mut actually_moved = false;
+        // The velocity the keys and the stick add every 60Hz frame.
+        let mut push = Vec2::ZERO;
+
+        // If the camera is in FirstPerson mode, we use the mouse to look around.
+        if input.first_person {
+            // Sensitivity multiplier for the mouse look.
+            let sensitivity = 3.0;
+            // Update yaw based on horizontal mouse movement.
+            self.yaw += input.look_delta.x * sensitivity;
+            // Update pitch based on vertical mouse movement.
+            self.pitch -= input.look_delta.y * sensitivity;
+            // Clamp pitch to prevent the player from looking 360 degrees vertically.
+            // 1.5 radians is approximately 85 degrees.
+            self.pitch = self.pitch.clamp(-1.5, 1.5);
+        }
+
+        // Handle forward and backward movement based on the current yaw direction.
+        if input.forward {
+            // Use trigonometry to calculate the forward movement vector.
+            push.x += self.yaw.sin() * accel;
+            push.y += self.yaw.cos() * accel;
+            actually_moved = true;
+        }
+        if input.back {
+            // Moving backward: subtract the forward vector.
+            push.x -= self.yaw.sin() * accel;
+            push.y -= self.yaw.cos() * accel;
+            actually_moved = true;
+        }
+
+        // Handle turning (ThirdPerson) or strafing (FirstPerson).
+        if input.left {
+            if input.first_person {
+                // In FPS mode, 'A' strafes left (moving perpendicular to the look direction).
+                // Subtracting PI/2 from yaw shifts the direction by -90 degrees.
+                push.x += (self.yaw - FRAC_PI_2).sin() * accel;
+                push.y += (self.yaw - FRAC_PI_2).cos() * accel;
+                actually_moved = true;
+            } else {
+                // In ThirdPerson mode, 'A' rotates the pet to the left.
+                self.yaw -= rotation_speed;
+            }
+        }
+        if input.right {
+            if input.first_person {
+                // In FPS mode, 'D' strafes right.
+                // Adding PI/2 to yaw shifts the direction by 90 degrees.
+                push.x += (self.yaw + FRAC_PI_2).sin() * accel;
+                push.y += (self.yaw + FRAC_PI_2).cos() * accel;
+                actually_moved = true;
+            } else {
+                // In ThirdPerson mode, 'D' rotates the pet to the right.
+                self.yaw += rotation_speed;
+            }
+        }
+
+        // The gamepad stick works like the keys above, as far as it is pushed: forward and back along
+        // the way the pet faces, and sideways it strafes in first person or turns in third person.
+        let stick = input.stick;
+        if stick.y != 0.0 {
+            push.x += self.yaw.sin() * accel * stick.y;
+            push.y += self.yaw.cos() * accel * stick.y;
+            actually_moved = true;
+        }
+        if stick.x != 0.0 {
+            if input.first_person {
+                push.x += (self.yaw + FRAC_PI_2).sin() * accel * stick.x;
+                push.y += (self.yaw + FRAC_PI_2).cos() * accel * stick.x;
+                actually_moved = true;
+            } else {
+                self.yaw += rotation_speed * stick.x;
+            }
+        }
+
+        self.sprinting = input.sprint && actually_moved;
+
+        // Apply friction (same as the ball; wet ground is slippery) and the push, and move the pet.
+        // Both are worked out as if they were applied one 60Hz frame at a time, so the pet reaches
+        // the same speed and covers the same distance at any tick length.
+        let f = weather.friction;
+        let start = Vec2::new(self.vx, self.vz);
+        let (velocity, moved) = if f < 1.0 {
+            let friction = f.powf(frames);
+            // How much of the starting velocity is left, summed over every frame of this tick.
+            let carried = f * (1.0 - friction) / (1.0 - f);
+            (start * friction + push * (1.0 - friction) / (1.0 - f), start * carried + push * (frames - carried) / (1.0 - f))
+        } else {
+            let velocity = start + push * frames;
+            (velocity, velocity * frames)
+        };
+        (self.vx, self.vz) = (velocity.x, velocity.y);
+        self.x += moved.x;
+        self.z += moved.y;
+
+        // Gravity pulls a jumping pet back down. It lands squashed flat, more so the harder it came down,
+        // and springs back into shape.
+        if self.jumps > 0 {
+            self.vy -= config.jump.gravity * frames;
+            self.height += self.vy * frames;
+            if self.height <= 0.0 {
+                self.squash = (-self.vy / config.jump.speed).min(1.0);
+                self.height = 0.0;
+                self.vy = 0.0;
+                self.jumps = 0;
+            }
+        }
+        self.squash *= SQUASH_RECOVERY.powf(frames);
+
+        // Calculate the magnitude of movement to determine the rolling speed.
+        let speed = (self.vx * self.vx + self.vz * self.vz).sqrt() * frames;
+        // Calculate the dot product between velocity and look direction.
+        // This helps determine if the pet is moving "forward" or "backward" relative to its face.
+        let dot = self.vx * self.yaw.sin() + self.vz * self.yaw.cos();
+
+        // Update the vertical rotation (rolling) based on movement direction.
+        if dot > 0.0 {
+            // Moving forward: roll one way.
+            self.rotation_v += speed * 5.0;
+        } else {
+            // Moving backward: roll the other way.
+            self.rotation_v -= speed * 5.0;
+        }
+
+        // If the pet is moving, update its stats slightly.
+        if actually_moved {
+            // Consumes energy and increases hunger when moving.
+            self.energy -= 0.05 * frames * species.energy_decay * weather.drain;
+            self.hunger += 0.02 * frames * weather.drain;
+            // Sprinting wears the pet out and makes it hungry a lot faster.
+            if self.sprinting {
+                self.energy -= config.sprint.energy_per_second * dt * species.energy_decay * weather.drain;
+                self.hunger += config.sprint.hunger_per_second * dt * weather.drain;
+            }
+        }
+
+        // Game Over Condition: if the pet gets too hungry or runs out of energy.
+        if self.hunger >= 100.0 {
+            self.die(now, DeathCause::Starvation);
+        } else if self.energy <= 0.0 {
+            self.die(now, DeathCause::Exhaustion);
+        }
+
+        // Clamp stats between 0.0 and 100.0 to keep them within valid ranges for the HUD.
+        self.hunger = self.hunger.clamp(0.0, 100.0);
+        self.happiness = self.happiness.clamp(0.0, 100.0);
+        self.energy = self.energy.clamp(0.0, 100.0);
+    }
+
+    // Keep the pet out of the obstacles it walked into. The pet is pushed back out and the part of
+    // its velocity going into the obstacle is dropped, so it slides along trunks and cacti.
+    pub fn collide(&mut self, obstacles: &[Collider]) {
+        let mut pos = Vec2::new(self.x, self.z);
+        if let Some(normal) = terrain::push_out(&mut pos, self.radius(), obstacles) {
+            self.x = pos.x;
+            self.z = pos.y;
+            let velocity = Vec2::new(self.vx, self.vz);
+            let into = velocity.dot(normal);
+            if into < 0.0 {
+                self.vx -= normal.x * into;
+                self.vz -= normal.y * into;
+            }
+        }
+    }
+
+    // Mark the pet as dead and record the time and cause of death to freeze the survival timer.
+    pub fn die(&mut self, now: f64, cause: DeathCause) {
+        self.is_alive = false;
+        if self.death_time.is_none() {
+            self.death_time = Some(now);
+            self.death_cause = Some(cause);
+        }
+    }
+
+    // A bug at (from_x, from_z) bites the pet: it loses health and is knocked away from the bug.
+    // Right after a bite the pet is briefly invulnerable, so a crowd of bugs cannot drain it in one tick.
+    // A sleeping pet is caught off guard: the bite hurts more, and wakes it up.
+    // An egg's shell keeps the bugs out. Returns true if the bite landed.
+    pub fn take_damage(&mut self, damage: f32, from_x: f32, from_z: f32, now: f64, health: &HealthConfig) -> bool {
+        if !self.is_alive || self.invulnerable > 0.0 || self.stage == LifeStage::Egg {
+            return false;
+        }
+        let damage = if self.asleep { damage * health.sleep_damage } else { damage };
+        let damage = damage * self.form.map_or(1.0, Form::damage);
+        self.asleep = false;
+        self.health = (self.health - damage).max(0.0);
+        self.invulnerable = health.invulnerability;
+
+        // Knock the pet straight away from the bug. A bug right on top of the pet pushes it backwards.
+        let (dx, dz) = (self.x - from_x, self.z - from_z);
+        let dist = (dx * dx + dz * dz).sqrt();
+        let (dir_x, dir_z) = if dist > 0.001 { (dx / dist, dz / dist) } else { (-self.yaw.sin(), -self.yaw.cos()) };
+        self.vx = dir_x * health.knockback;
+        self.vz = dir_z * health.knockback;
+
+        if self.health <= 0.0 {
+            self.die(now, DeathCause::Bugs);
+        }
+        true
+    }
+
+    // The radius of the pet's body, which grows with it.
+    pub fn radius(&self) -> f32 {
+        PET_RADIUS * self.stage.size()
+    }
+
+    // Move on to the life stage of the pet's age. The pet's adult form is settled when it first
+    // grows up, by its average care score. Pets that never had their care counted are average.
+    fn grow(&mut self, now: f64, growth: &GrowthConfig) {
+        self.stage = LifeStage::at(self.survival_time(now), growth);
+        if self.stage >= LifeStage::Adult && self.form.is_none() {
+            let care = if self.care_seconds > 0.0 { self.care_total / self.care_seconds } else { 50.0 };
+            self.form = Some(Form::from_care(care, growth));
+        }
+    }
+
+    // How long this pet has been alive, measured in simulated seconds.
+    pub fn survival_time(&self, now: f64) -> f64 {
+        self.death_time.unwrap_or(now) - self.start_time
+    }
+
+    // Method to reduce hunger by feeding the pet a food item from its inventory, which is used up.
+    pub fn feed(&mut self, kind: ItemKind, item: &ItemConfig) -> bool {
+        if self.is_alive && self.is_stationary && self.inventory.take(kind) {
+            self.apply(item);
+            return true;
+        }
+        false
+    }
+
+    // Method to increase happiness by playing with a toy. The toy is kept.
+    // The world makes sure there is a toy nearby: one the pet carries, or the ball.
+    pub fn play(&mut self, toy: &ItemConfig) -> bool {
+        if self.is_alive && self.is_stationary {
+            // Playing increases happiness but usually costs energy.
+            self.apply(toy);
+            return true;
+        }
+        false
+    }
+
+    // Method to heal the pet with medicine from the inventory. It can be taken on the move.
+    pub fn take_medicine(&mut self, kind: ItemKind, item: &ItemConfig) -> bool {
+        if self.is_alive && self.inventory.take(kind) {
+            self.apply(item);
+            return true;
+        }
+        false
+    }
+
+    // Change the stats by an item's effects. The stats are clamped by the next update,
+    // except health, which is capped right away. Items never take health away.
+    fn apply(&mut self, item: &ItemConfig) {
+        self.hunger -= item.nutrition;
+        self.happiness += item.happiness;
+        self.energy += item.energy;
+        self.health = (self.health + item.health).min(100.0);
+    }
+
+    // Jump off the ground, or jump once more in mid-air if the pet has plenty of energy.
+    // Every jump costs energy. Returns false if the pet cannot jump right now.
+    pub fn jump(&mut self, jump: &JumpConfig) -> bool {
+        let can_jump = match self.jumps {
+            0 => true,
+            1 => self.energy >= jump.double_jump_energy,
+            _ => false,
+        };
+        if !self.is_alive || self.asleep || !can_jump || self.energy < jump.energy_cost {
+            return false;
+        }
+        self.vy = jump.speed;
+        self.jumps += 1;
+        self.energy -= jump.energy_cost;
+        true
+    }
+
+    // Dash: a sudden burst of speed the way the pet is going, or the way it faces if it is standing still.
+    // It costs energy and has to recharge before the next one. Returns false if the pet cannot dash right now.
+    pub fn dash(&mut self, sprint: &SprintConfig) -> bool {
+        if !self.is_alive || self.asleep || self.dash_cooldown > 0.0 || self.energy < sprint.dash_energy {
+            return false;
+        }
+        let facing = Vec2::new(self.yaw.sin(), self.yaw.cos());
+        let direction = Vec2::new(self.vx, self.vz).try_normalize().unwrap_or(facing);
+        self.vx = direction.x * sprint.dash_speed;
+        self.vz = direction.y * sprint.dash_speed;
+        self.energy -= sprint.dash_energy;
+        self.dash_cooldown = sprint.dash_cooldown;
+        true
+    }
+
+    // Method to put the pet to sleep, so it restores energy over time in update.
+    pub fn sleep(&mut self) -> bool {
+        if self.is_alive && self.is_stationary && !self.asleep {
+            self.asleep = true;
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,772 @@
+// Everything in this module only reads the World and draws it with macroquad.
+// No gameplay state is changed here.
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+
+use gaemthesecond::ball::BALL_RADIUS;
+use gaemthesecond::bug::{Bug, BugKind};
+use gaemthesecond::chunks::{Chunk, GROUND_STEPS};
+use gaemthesecond::config::{Part, SpeciesConfig};
+use gaemthesecond::daycycle;
+use gaemthesecond::growth::{Form, LifeStage};
+use gaemthesecond::inventory::{Inventory, ItemKind};
+use gaemthesecond::mood::Mood;
+use gaemthesecond::pet::Pet;
+use gaemthesecond::terrain::{self, Biome, FeatureKind, WATER_LEVEL};
+use gaemthesecond::weather::WeatherKind;
+use gaemthesecond::world::World;
+
+// How long the streak behind the pet lasts after a dash, in seconds.
+const DASH_TRAIL_TIME: f32 = 0.4;
+
+// Define an enumeration to keep track of the current camera perspective.
+// We use #[derive(Debug)] to allow the enum to be formatted as a string for display messages.
+#[derive(Debug)]
+pub enum CameraMode {
+    // FirstPerson mode: The camera is placed near the pet's "head" looking forward.
+    FirstPerson,
+    // ThirdPerson mode: The camera follows behind the pet from a distance.
+    ThirdPerson,
+}
+
+// Utility function to draw a progress bar on the screen (UI/HUD).
+pub fn draw_bar(y: f32, label: &str, value: f32, color: Color) {
+    // Draw the text label for the stat.
+    draw_text(label, 20.0, y + 15.0, 20.0, DARKGRAY);
+    // Draw the background of the bar (the gray track).
+    draw_rectangle(120.0, y, 200.0, 20.0, LIGHTGRAY);
+    // Draw the filled portion of the bar representing the stat value.
+    // The width is 'value * 2.0' because the max value is 100 and the bar width is 200.
+    draw_rectangle(120.0, y, value * 2.0, 20.0, color);
+}
+
+// Configure the 3D camera based on the current camera mode.
+// In third person, orbit swings the camera around the pet (x, in radians) and raises or lowers it (y).
+pub fn set_world_camera(world: &World, camera_mode: &CameraMode, orbit: Vec2) {
+    let pet = &world.pet;
+    match camera_mode {
+        CameraMode::ThirdPerson => {
+            // Third Person: Camera follows the pet from behind.
+            // The camera looks towards the pet's position.
+            // It follows a jumping pet up only halfway, so the view does not jump along with it.
+            let target = vec3(pet.x, pet.y + 1.0 + pet.height * 0.5, pet.z);
+            // Position is behind the pet based on its yaw and 10 units away, 6 units above it.
+            let angle = pet.yaw + orbit.x;
+            let mut position = vec3(pet.x - angle.sin() * 10.0, pet.y + 6.0 + orbit.y, pet.z - angle.cos() * 10.0);
+            // Raise the camera wherever the view of the pet would pass through a hill,
+            // so the camera never looks at the pet from inside the ground.
+            for step in 1..=10 {
+                let t = step as f32 / 10.0;
+                let point = target.lerp(position, t);
+                let clearance = terrain::ground_height(world.seed, point.x, point.z) + 1.0;
+                if point.y < clearance {
+                    position.y += (clearance - point.y) / t;
+                }
+            }
+            set_camera(&Camera3D {
+                position,
+                // "Up" vector defines which way is up in world space.
+                up: vec3(0.0, 1.0, 0.0),
+                target,
+                ..Default::default()
+            });
+        }
+        CameraMode::FirstPerson => {
+            // First Person: Camera is inside/at the head of the pet.
+            // Calculate the direction the pet is looking using spherical coordinates.
+            let look_dir = vec3(
+                pet.yaw.sin() * pet.pitch.cos(),
+                pet.pitch.sin(),
+                pet.yaw.cos() * pet.pitch.cos(),
+            );
+            // Position the "head" with a slight vertical bobbing effect using a sine wave.
+            // Shifted forward and slightly higher to prevent being "inside" the body.
+            // The head rises and falls with the pet's jumps on top of the bobbing.
+            let head_pos = vec3(pet.x, pet.y + pet.height + 1.4 + (get_time().sin() * 0.1) as f32, pet.z) + look_dir * 1.2;
+            set_camera(&Camera3D {
+                position: head_pos,
+                up: vec3(0.0, 1.0, 0.0),
+                // The target is just ahead of the camera in the look direction.
+                target: head_pos + look_dir,
+                ..Default::default()
+            });
+        }
+    }
+}
+
+// Draw the sky, the procedural terrain and every entity in the world.
+pub fn draw_world(world: &World) {
+    let pet = &world.pet;
+
+    // Draw the Sun: A bright yellow sphere in the distance, and the pale moon opposite it.
+    // They circle far around the pet with the in-game clock, rising in the east and setting in the west.
+    let angle = (world.hour() - 6.0) / 24.0 * TAU;
+    let sun_dir = vec3(angle.cos(), angle.sin(), 0.3).normalize();
+    let sky_center = vec3(pet.x, pet.y, pet.z);
+    draw_sphere(sky_center + sun_dir * 150.0, 10.0, None, YELLOW);
+    draw_sphere(sky_center - sun_dir * 150.0, 6.0, None, Color::new(0.9, 0.9, 1.0, 1.0));
+
+    // Procedural Infinite Terrain Generation (Chunk-based rendering).
+    // The chunks come from the world's chunk cache, which the simulation keeps filled around the pet.
+    let chunks = &world.chunks;
+    let chunk_size = chunks.chunk_size();
+    // Render distance: number of chunks to draw in each direction around the player.
+    let view_dist = world.config.terrain.view_dist as i32;
+    // Determine which chunk the pet is currently standing in.
+    let (p_chunk_x, p_chunk_z) = chunks.chunk_of(pet.x, pet.z);
+
+    // Iterate through all chunks within the render distance.
+    for cx in (p_chunk_x - view_dist)..=(p_chunk_x + view_dist) {
+        for cz in (p_chunk_z - view_dist)..=(p_chunk_z + view_dist) {
+            // Chunks that were not generated yet (before the first tick) are skipped.
+            let Some(chunk) = chunks.get(cx, cz) else {
+                continue;
+            };
+            // Calculate world coordinates for the corner of the chunk.
+            let x = cx as f32 * chunk_size;
+            let z = cz as f32 * chunk_size;
+
+            // Draw the ground of this chunk, shaped and coloured by the heightmap and the biomes,
+            // and the water surface over any part of it that lies under water.
+            draw_ground(chunk, x, z, chunk_size);
+            if chunk.has_water() {
+                let half = chunk_size / 2.0;
+                draw_plane(vec3(x + half, WATER_LEVEL, z + half), vec2(half, half), None, Color::new(0.2, 0.45, 0.9, 0.6));
+            }
+
+            // Add "Minecraft-like" environmental features (trees and flowers) to the chunk.
+            // Picked flowers and emptied puddles are shown as such until they grow back.
+            for (index, feature) in chunk.features.iter().enumerate() {
+                let (fx, fy, fz) = (feature.x, feature.y, feature.z);
+                let ripe = world.forage.is_ripe(cx, cz, index, world.time);
+                match feature.kind {
+                    FeatureKind::Bush => {
+                        // Grassy bush: a large green sphere on the ground.
+                        draw_sphere(vec3(fx, fy + 0.2, fz), 1.5, None, LIME);
+                    }
+                    FeatureKind::Tree => {
+                        // Tree: both trunk and leaves are placed together at the same (fx, fz).
+                        // Trunk: a brown cube at the base.
+                        draw_cube(vec3(fx, fy + 1.0, fz), vec3(0.5, 2.0, 0.5), None, BROWN);
+                        // Leaves: a green sphere on top of the trunk.
+                        draw_sphere(vec3(fx, fy + 2.0, fz), 1.2, None, GREEN);
+                    }
+                    FeatureKind::Flower => {
+                        // Flower: a red berry on a stem, or just a green bud once it was picked.
+                        draw_cube(vec3(fx, fy + 0.2, fz), vec3(0.1, 0.5, 0.1), None, GREEN);
+                        if ripe {
+                            draw_sphere(vec3(fx, fy + 0.5, fz), 0.2, None, RED);
+                        } else {
+                            draw_sphere(vec3(fx, fy + 0.45, fz), 0.08, None, DARKGREEN);
+                        }
+                    }
+                    FeatureKind::Puddle => {
+                        // Puddle: a flat blue disc of water, or a patch of mud once it was drunk dry.
+                        let color = if ripe { BLUE } else { Color::new(0.45, 0.33, 0.2, 1.0) };
+                        draw_cylinder(vec3(fx, fy + 0.02, fz), 0.8, 0.8, 0.04, None, color);
+                    }
+                    FeatureKind::Cactus => {
+                        // Cactus: a tall green column with one arm on each side.
+                        draw_cube(vec3(fx, fy + 0.9, fz), vec3(0.4, 1.8, 0.4), None, DARKGREEN);
+                        draw_cube(vec3(fx + 0.35, fy + 1.1, fz), vec3(0.3, 0.15, 0.15), None, DARKGREEN);
+                        draw_cube(vec3(fx + 0.45, fy + 1.35, fz), vec3(0.15, 0.5, 0.15), None, DARKGREEN);
+                        draw_cube(vec3(fx - 0.35, fy + 0.8, fz), vec3(0.3, 0.15, 0.15), None, DARKGREEN);
+                        draw_cube(vec3(fx - 0.45, fy + 1.05, fz), vec3(0.15, 0.5, 0.15), None, DARKGREEN);
+                    }
+                }
+            }
+        }
+    }
+
+    // Draw game entities if the pet is still alive.
+    if pet.is_alive {
+        draw_pet(pet, world.config.species.get(&pet.species));
+        // A jumping pet casts a shadow on the ground below, like the ball.
+        if pet.height > 0.05 {
+            let shadow = Color::new(0.0, 0.0, 0.0, 0.3);
+            let radius = pet.radius() * 0.9;
+            draw_cylinder(vec3(pet.x, pet.y + 0.02, pet.z), radius, radius, 0.02, None, shadow);
+        }
+        // A sprinting pet kicks up a short trail of dust behind it, and a dash leaves a long streak.
+        let dashing = pet.dash_cooldown > 0.0 && pet.dash_cooldown > world.config.sprint.dash_cooldown - DASH_TRAIL_TIME;
+        if pet.sprinting || dashing {
+            let (count, color) = if dashing { (8, SKYBLUE) } else { (4, Color::new(0.8, 0.7, 0.5, 1.0)) };
+            let center = vec3(pet.x, pet.y + pet.height + pet.radius() * 0.5, pet.z);
+            let velocity = vec3(pet.vx, 0.0, pet.vz);
+            for i in 1..=count {
+                let fade = 1.0 - i as f32 / (count + 1) as f32;
+                let color = Color::new(color.r, color.g, color.b, 0.5 * fade);
+                draw_sphere(center - velocity * 1.5 * i as f32, pet.radius() * 0.5 * fade, None, color);
+            }
+        }
+
+        // Draw the Bugs. Only bugs within the rendered chunks are drawn, found through the spatial grid.
+        let draw_distance = (view_dist + 1) as f32 * chunk_size;
+        for i in world.bug_grid.query(pet.x, pet.z, draw_distance) {
+            let bug = &world.bugs[i];
+            let (dx, dz) = (bug.x - pet.x, bug.z - pet.z);
+            if dx * dx + dz * dz > draw_distance * draw_distance {
+                continue;
+            }
+            draw_bug(bug, terrain::ground_height(world.seed, bug.x, bug.z));
+        }
+
+        // Draw the squashed bugs as flat dark splats with a green smear.
+        for squashed in &world.squashed {
+            let ground = terrain::ground_height(world.seed, squashed.x, squashed.z);
+            draw_cube(vec3(squashed.x, ground + 0.03, squashed.z), vec3(0.6, 0.05, 0.6), None, DARKGRAY);
+            draw_sphere(vec3(squashed.x, ground + 0.05, squashed.z), 0.12, None, DARKGREEN);
+        }
+
+        // Draw the items lying around, slowly spinning so they stand out.
+        let spin = get_time() as f32 * 2.0;
+        for item in &world.items {
+            let ground = terrain::ground_height(world.seed, item.x, item.z);
+            draw_item(item.kind, vec3(item.x, ground + 0.4, item.z), spin);
+        }
+
+        // Render the Ball.
+        let ball = &world.ball;
+        let ball_pos = vec3(ball.x, ball.y + ball.height + BALL_RADIUS, ball.z);
+        // Draw the ball body (white sphere).
+        draw_sphere(ball_pos, BALL_RADIUS, None, WHITE);
+        // Draw coloured spots on the ball that turn with it as it rolls and spins.
+        let orientation = Quat::from_array(ball.orientation);
+        for (spot, color) in [(Vec3::Z, RED), (-Vec3::Z, BLUE), (Vec3::X, RED), (-Vec3::X, BLUE)] {
+            draw_sphere(ball_pos + orientation * spot * 0.45, 0.1, None, color);
+        }
+        // A ball in the air casts a shadow on the ground below, so its height is easy to judge.
+        if ball.height > 0.05 && !ball.held {
+            let shadow = Color::new(0.0, 0.0, 0.0, 0.3);
+            draw_cylinder(vec3(ball.x, ball.y + 0.02, ball.z), BALL_RADIUS * 0.9, BALL_RADIUS * 0.9, 0.02, None, shadow);
+        }
+
+        // While a throw is wound up, a dotted line on the ground shows where the ball is aimed,
+        // getting longer with the power of the throw.
+        let fetch = &world.fetch;
+        if fetch.charge > 0.0 {
+            let power = fetch.power(&world.config.fetch);
+            let (dir_x, dir_z) = (pet.yaw.sin(), pet.yaw.cos());
+            for step in 1..=(3 + (power * 12.0) as u32) {
+                let (x, z) = (ball.x + dir_x * step as f32, ball.z + dir_z * step as f32);
+                draw_sphere(vec3(x, terrain::ground_height(world.seed, x, z) + 0.1, z), 0.1, None, mix_color(YELLOW, RED, power));
+            }
+        }
+        // During a fetch, a little flag marks the spot the ball has to be brought back to.
+        if fetch.active() {
+            let (x, z) = (fetch.origin_x, fetch.origin_z);
+            let ground = terrain::ground_height(world.seed, x, z);
+            draw_cube(vec3(x, ground + 1.0, z), vec3(0.08, 2.0, 0.08), None, DARKGRAY);
+            draw_cube(vec3(x + 0.3, ground + 1.75, z), vec3(0.5, 0.35, 0.05), None, RED);
+        }
+    }
+
+    draw_weather(world);
+}
+
+// Blend from colour a to colour b as t goes from 0 to 1.
+fn mix_color(a: Color, b: Color, t: f32) -> Color {
+    Color::new(a.r + (b.r - a.r) * t, a.g + (b.g - a.g) * t, a.b + (b.b - a.b) * t, a.a + (b.a - a.a) * t)
+}
+
+// The colour of the sky: blue in the day, dark blue at night, and orange around dawn and dusk.
+// Rain clouds turn it grey.
+pub fn sky_color(world: &World) -> Color {
+    let light = world.daylight();
+    let night = Color::new(0.03, 0.04, 0.12, 1.0);
+    let dusk = Color::new(0.95, 0.55, 0.3, 1.0);
+    let sky = if light < 0.5 { mix_color(night, dusk, light * 2.0) } else { mix_color(dusk, SKYBLUE, light * 2.0 - 1.0) };
+    if world.weather.kind == WeatherKind::Rain {
+        let clouds = mix_color(night, Color::new(0.45, 0.47, 0.5, 1.0), light);
+        mix_color(sky, clouds, world.weather.intensity() * 0.7)
+    } else {
+        sky
+    }
+}
+
+// Darken the whole 3D scene at night, and tint it grey in the rain or orange in a heat wave.
+// Drawn in screen space, after the world and before the HUD.
+pub fn draw_ambient(world: &World) {
+    let dark = (1.0 - world.daylight()) * 0.55;
+    if dark > 0.0 {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.08, dark));
+    }
+    let tint = match world.weather.kind {
+        WeatherKind::Rain => Color::new(0.3, 0.3, 0.35, 0.2),
+        WeatherKind::Heat => Color::new(1.0, 0.55, 0.1, 0.12),
+        WeatherKind::Clear | WeatherKind::Wind => return,
+    };
+    let intensity = world.weather.intensity();
+    if intensity > 0.0 {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color { a: tint.a * intensity, ..tint });
+    }
+}
+
+// A repeatable pseudo random number from 0 to 1 for particle number i, so every particle keeps its
+// own place in the pattern from frame to frame. salt gives independent numbers for the same particle.
+fn scatter(i: u32, salt: u32) -> f32 {
+    let mut h = i.wrapping_mul(0x9E37_79B9) ^ salt.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    (h & 0xFFFF) as f32 / 65536.0
+}
+
+// Draw the particles of the current weather in a box around the pet: falling raindrops,
+// streaks of wind, or specks of heat shimmer rising from the ground. They are purely visual,
+// so they move with the real clock rather than the simulation.
+fn draw_weather(world: &World) {
+    const AREA: f32 = 40.0;
+    let weather = &world.weather;
+    let intensity = weather.intensity();
+    if intensity <= 0.0 {
+        return;
+    }
+    let pet = &world.pet;
+    let t = get_time() as f32;
+    // A spot in the box around the pet for particle i.
+    let spot = |i: u32| vec2(pet.x + (scatter(i, 1) - 0.5) * AREA, pet.z + (scatter(i, 2) - 0.5) * AREA);
+    match weather.kind {
+        WeatherKind::Clear => {}
+        WeatherKind::Rain => {
+            const HEIGHT: f32 = 15.0;
+            let color = Color::new(0.6, 0.7, 1.0, 0.6);
+            for i in 0..(600.0 * intensity) as u32 {
+                let p = spot(i);
+                let fall = (t * 20.0 + scatter(i, 3) * HEIGHT) % HEIGHT;
+                let top = vec3(p.x, pet.y + HEIGHT - fall, p.y);
+                draw_line_3d(top, top - vec3(0.0, 0.7, 0.0), color);
+            }
+        }
+        WeatherKind::Wind => {
+            let dir = Vec2::from_angle(weather.wind_angle);
+            let across = vec2(-dir.y, dir.x);
+            let color = Color::new(1.0, 1.0, 1.0, 0.5);
+            for i in 0..(80.0 * intensity) as u32 {
+                let along = (t * 15.0 + scatter(i, 1) * AREA) % AREA - AREA / 2.0;
+                let p = vec2(pet.x, pet.z) + dir * along + across * (scatter(i, 2) - 0.5) * AREA;
+                let start = vec3(p.x, pet.y + 0.5 + scatter(i, 3) * 4.0, p.y);
+                draw_line_3d(start, start + vec3(dir.x, 0.0, dir.y) * 1.5, color);
+            }
+        }
+        WeatherKind::Heat => {
+            const HEIGHT: f32 = 6.0;
+            let color = Color::new(1.0, 0.8, 0.4, 0.5);
+            for i in 0..(60.0 * intensity) as u32 {
+                let p = spot(i);
+                let rise = (t * 1.5 + scatter(i, 3) * HEIGHT) % HEIGHT;
+                let ground = terrain::ground_height(world.seed, p.x, p.y);
+                draw_sphere(vec3(p.x, ground + rise, p.y), 0.05, None, color);
+            }
+        }
+    }
+}
+
+// The colour of the ground in each biome.
+fn biome_color(biome: Biome) -> Color {
+    match biome {
+        Biome::Meadow => GREEN,
+        Biome::Forest => DARKGREEN,
+        Biome::Desert => Color::new(0.93, 0.84, 0.56, 1.0),
+        // The muddy bottom of a pond, seen through the water.
+        Biome::Pond => Color::new(0.55, 0.5, 0.35, 1.0),
+    }
+}
+
+// Draw the ground of one chunk as a mesh through its height samples. Each corner takes the colour
+// of its biome, darkened on slopes that face away from the sun so the hills are easy to see.
+fn draw_ground(chunk: &Chunk, x: f32, z: f32, chunk_size: f32) {
+    let step = chunk_size / GROUND_STEPS as f32;
+    let sun = vec3(0.5, 1.0, 0.5).normalize();
+    let height = |i: usize, j: usize| chunk.heights[Chunk::sample_index(i, j)];
+    let mut vertices = Vec::with_capacity((GROUND_STEPS + 1) * (GROUND_STEPS + 1));
+    for j in 0..=GROUND_STEPS {
+        for i in 0..=GROUND_STEPS {
+            // The slope from the neighbouring samples (one-sided at the edges of the chunk).
+            let dx = (height((i + 1).min(GROUND_STEPS), j) - height(i.saturating_sub(1), j)) / step;
+            let dz = (height(i, (j + 1).min(GROUND_STEPS)) - height(i, j.saturating_sub(1))) / step;
+            let normal = vec3(-dx, 2.0, -dz).normalize();
+            let light = 0.6 + 0.4 * normal.dot(sun).max(0.0);
+            let color = biome_color(chunk.biomes[Chunk::sample_index(i, j)]);
+            let shaded = Color::new(color.r * light, color.g * light, color.b * light, 1.0);
+            let position = vec3(x + i as f32 * step, height(i, j), z + j as f32 * step);
+            vertices.push(Vertex::new2(position, Vec2::ZERO, shaded));
+        }
+    }
+    let mut indices = Vec::with_capacity(GROUND_STEPS * GROUND_STEPS * 6);
+    for j in 0..GROUND_STEPS {
+        for i in 0..GROUND_STEPS {
+            // Two triangles for every square between four samples.
+            let corner = Chunk::sample_index(i, j) as u16;
+            let below = Chunk::sample_index(i, j + 1) as u16;
+            indices.extend_from_slice(&[corner, corner + 1, below, corner + 1, below + 1, below]);
+        }
+    }
+    draw_mesh(&Mesh { vertices, indices, texture: None });
+}
+
+// Draw the pet at its current size. An egg is just a shell with a spot of the species' colour.
+// After hatching the pet is its round body with the parts of its species on top, tinted by its
+// adult form and going grey once it is old, and the face shows its mood.
+fn draw_pet(pet: &Pet, species: &SpeciesConfig) {
+    let rgb = |[r, g, b]: [f32; 3]| Color::new(r, g, b, 1.0);
+    // Every size and offset below is for a grown-up pet and gets scaled by the pet's life stage.
+    let size = pet.stage.size();
+    if pet.stage == LifeStage::Egg {
+        // The egg wobbles a little as the pet inside moves about.
+        let wobble = (get_time() * 3.0).sin() as f32 * 0.05;
+        let base = vec3(pet.x + wobble, pet.y + size, pet.z);
+        let shell = Color::new(0.95, 0.92, 0.8, 1.0);
+        draw_sphere(base, size, None, shell);
+        draw_sphere(base + vec3(0.0, size * 0.6, 0.0), size * 0.75, None, shell);
+        draw_sphere(base + vec3(size * 0.5, size * 0.3, size * 0.5), size * 0.2, None, rgb(species.color));
+        return;
+    }
+
+    // Calculate the 3D position of the pet with bobbing animation.
+    // A sleeping pet lies still, only breathing slowly.
+    let bob = if pet.asleep { (get_time() * 1.5).sin() * 0.05 - 0.1 } else { get_time().sin() * 0.2 };
+    let pet_pos = vec3(pet.x, pet.y + pet.height + (1.0 + bob as f32) * size, pet.z);
+
+    // Squash and stretch: the pet stretches out while it flies up or falls, and lands squashed flat.
+    // The whole model is scaled around the point under it, keeping its volume about the same.
+    let stretch = 1.0 + (pet.vy.abs() * 1.5).min(0.3) - pet.squash * 0.4;
+    let feet = vec3(pet.x, pet.y + pet.height, pet.z);
+    let squeeze = 1.0 / stretch.sqrt();
+    let model = Mat4::from_translation(feet) * Mat4::from_scale(vec3(squeeze, stretch, squeeze)) * Mat4::from_translation(-feet);
+    // SAFETY: the model matrix is only pushed and popped on the main thread, between other draw calls.
+    unsafe { get_internal_gl().quad_gl.push_model_matrix(model) };
+
+    // Calculate the direction vectors for the face and sides based on yaw.
+    let face_dir_x = pet.yaw.sin();
+    let face_dir_z = pet.yaw.cos();
+    let side_x = pet.yaw.cos();
+    let side_z = -pet.yaw.sin();
+
+    // The body and its parts are tinted by the pet's adult form, and greyed in old age.
+    // Everything flashes red while the pet is invulnerable after a bug bite.
+    let hurt = pet.invulnerable > 0.0 && (get_time() * 10.0) as i64 % 2 == 0;
+    let tint = |color: [f32; 3]| {
+        let color = match pet.form {
+            Some(Form::Radiant) => mix_color(rgb(color), GOLD, 0.35),
+            Some(Form::Gloomy) => mix_color(rgb(color), Color::new(0.45, 0.35, 0.55, 1.0), 0.5),
+            Some(Form::Sturdy) | None => rgb(color),
+        };
+        match pet.stage {
+            _ if hurt => RED,
+            LifeStage::Elder => mix_color(color, GRAY, 0.4),
+            _ => color,
+        }
+    };
+
+    // Draw the main body of the pet (a sphere).
+    draw_sphere(pet_pos, size, None, tint(species.color));
+
+    // Draw the parts of the species' model. Rolling parts, like the blob's spots, turn around the
+    // pet's sideways axis with rotation_v, to visualize movement.
+    let (roll_sin, roll_cos) = pet.rotation_v.sin_cos();
+    let place = |[across, up, forward]: [f32; 3], rolls: bool| {
+        let (up, forward) = if rolls { (up * roll_cos + forward * roll_sin, forward * roll_cos - up * roll_sin) } else { (up, forward) };
+        pet_pos + vec3(side_x * across + face_dir_x * forward, up, side_z * across + face_dir_z * forward) * size
+    };
+    for part in &species.parts {
+        match *part {
+            Part::Sphere { at, radius, color, rolls } => draw_sphere(place(at, rolls), radius * size, None, tint(color)),
+            Part::Cube { at, size: extent, color } => draw_cube(place(at, false), Vec3::from(extent) * size, None, tint(color)),
+        }
+    }
+
+    // Positioning the Eyes relative to the body and rotation.
+    let eye_offset_y = 0.3 * size;
+    let eye_dist = 0.8 * size; // Distance from center to front.
+    let eye_spacing = 0.4 * size; // Distance between eyes.
+
+    // Left eye position.
+    let eye_l = pet_pos + vec3(face_dir_x * eye_dist + side_x * eye_spacing, eye_offset_y, face_dir_z * eye_dist + side_z * eye_spacing);
+    // Right eye position.
+    let eye_r = pet_pos + vec3(face_dir_x * eye_dist - side_x * eye_spacing, eye_offset_y, face_dir_z * eye_dist - side_z * eye_spacing);
+
+    // The face shows the pet's mood. A point on the face, sideways from the middle and up or down.
+    let face = |side: f32, up: f32| pet_pos + vec3(face_dir_x * eye_dist + side_x * side, up, face_dir_z * eye_dist + side_z * side);
+    // A curved line of small spheres across the face, for smiles and frowns.
+    let curve = |width: f32, up: f32, bend: f32| {
+        for step in -3..=3 {
+            let t = step as f32 / 3.0;
+            draw_sphere(face(t * width * size, (up + bend * (1.0 - t * t)) * size), 0.05 * size, None, BLACK);
+        }
+    };
+
+    // Draw the eyes (black spheres), or closed eyes (flat lines) while the pet sleeps.
+    match pet.mood {
+        _ if pet.asleep => {
+            draw_cube(eye_l, vec3(0.3, 0.05, 0.3) * size, None, BLACK);
+            draw_cube(eye_r, vec3(0.3, 0.05, 0.3) * size, None, BLACK);
+        }
+        // Wide white eyes with tiny pupils.
+        Mood::Scared => {
+            for eye in [eye_l, eye_r] {
+                draw_sphere(eye, 0.2 * size, None, WHITE);
+                draw_sphere(eye + vec3(face_dir_x, 0.0, face_dir_z) * 0.15 * size, 0.07 * size, None, BLACK);
+            }
+        }
+        // Half closed eyes.
+        Mood::Bored | Mood::Sleepy => {
+            let height = if pet.mood == Mood::Sleepy { 0.06 } else { 0.12 };
+            draw_cube(eye_l, vec3(0.28, height, 0.28) * size, None, BLACK);
+            draw_cube(eye_r, vec3(0.28, height, 0.28) * size, None, BLACK);
+        }
+        // Small eyes under brows slanting down towards the middle.
+        Mood::Grumpy => {
+            draw_sphere(eye_l, 0.12 * size, None, BLACK);
+            draw_sphere(eye_r, 0.12 * size, None, BLACK);
+            for step in 0..3 {
+                let t = step as f32 / 2.0;
+                let (side, up) = (eye_spacing + (t * 0.3 - 0.15) * size, eye_offset_y + (0.14 + t * 0.1) * size);
+                draw_sphere(face(side, up), 0.05 * size, None, BLACK);
+                draw_sphere(face(-side, up), 0.05 * size, None, BLACK);
+            }
+        }
+        // Big shiny eyes.
+        Mood::Playful => {
+            for eye in [eye_l, eye_r] {
+                draw_sphere(eye, 0.18 * size, None, BLACK);
+                draw_sphere(eye + vec3(face_dir_x * 0.12, 0.06, face_dir_z * 0.12) * size, 0.05 * size, None, WHITE);
+            }
+        }
+        Mood::Content => {
+            draw_sphere(eye_l, 0.15 * size, None, BLACK);
+            draw_sphere(eye_r, 0.15 * size, None, BLACK);
+        }
+    }
+
+    // Draw the Mouth: a smile, a frown, an open "O", or a plain line (a black rectangle/cube).
+    let mouth_pos = pet_pos + vec3(face_dir_x * eye_dist, -0.3 * size, face_dir_z * eye_dist);
+    match pet.mood {
+        Mood::Playful => curve(0.25, -0.25, -0.12),
+        Mood::Grumpy => curve(0.22, -0.38, 0.1),
+        Mood::Scared => draw_sphere(mouth_pos, 0.12 * size, None, BLACK),
+        Mood::Bored | Mood::Sleepy => draw_cube(mouth_pos, vec3(0.2, 0.06, 0.06) * size, None, BLACK),
+        Mood::Content => draw_cube(mouth_pos, vec3(0.4, 0.1, 0.1) * size, None, BLACK),
+    }
+    // SAFETY: see above.
+    unsafe { get_internal_gl().quad_gl.pop_model_matrix() };
+}
+
+// Draw one bug with the model of its kind, facing the way it is moving.
+// ground is the height of the ground below the bug.
+fn draw_bug(bug: &Bug, ground: f32) {
+    // Bugs hover slightly above the ground; flyers add their hop on top.
+    let bob = (get_time() * 5.0).sin() as f32 * 0.1;
+    let radius = bug.kind.radius();
+    let pos = vec3(bug.x, ground + 0.2 + radius + bug.y + bob, bug.z);
+    // Unit vectors pointing forward and to the side of the bug.
+    let forward = vec3(bug.heading.sin(), 0.0, bug.heading.cos());
+    let side = vec3(forward.z, 0.0, -forward.x);
+    let eyes = |color: Color| {
+        let eye = pos + forward * radius * 0.8 + vec3(0.0, radius * 0.3, 0.0);
+        draw_sphere(eye + side * radius * 0.35, radius * 0.18, None, color);
+        draw_sphere(eye - side * radius * 0.35, radius * 0.18, None, color);
+    };
+
+    match bug.kind {
+        // Swarmers are small black spheres with red eyes.
+        BugKind::Swarmer => {
+            draw_sphere(pos, radius, None, BLACK);
+            eyes(RED);
+        }
+        // Flankers are long and purple: a body with a head in front, and yellow eyes.
+        BugKind::Flanker => {
+            draw_sphere(pos - forward * radius * 0.7, radius * 0.9, None, DARKPURPLE);
+            draw_sphere(pos + forward * radius * 0.2, radius * 0.8, None, PURPLE);
+            eyes(YELLOW);
+        }
+        // Beetles are big brown shells with a ridge down the middle, fading with every hit they take.
+        BugKind::Beetle => {
+            let wear = bug.health as f32 / BugKind::Beetle.max_health() as f32;
+            let shell = Color::new(0.3 + 0.3 * wear, 0.2 + 0.15 * wear, 0.1, 1.0);
+            draw_sphere(pos, radius, None, shell);
+            for step in [-0.5, 0.0, 0.5] {
+                draw_sphere(pos + forward * radius * step + vec3(0.0, radius * 0.85, 0.0), radius * 0.2, None, DARKBROWN);
+            }
+            eyes(ORANGE);
+        }
+        // Flyers are small green bugs with two flapping, see-through wings.
+        BugKind::Flyer => {
+            draw_sphere(pos, radius, None, DARKGREEN);
+            let wing_pos = pos + vec3(0.0, radius + (get_time() * 30.0).sin() as f32 * 0.1, 0.0);
+            let wing = Color::new(1.0, 1.0, 1.0, 0.6);
+            draw_cube(wing_pos + side * radius * 1.4, vec3(0.3, 0.02, 0.3), None, wing);
+            draw_cube(wing_pos - side * radius * 1.4, vec3(0.3, 0.02, 0.3), None, wing);
+            eyes(RED);
+        }
+    }
+}
+
+// The main colour of each item, also used for its hotbar icon.
+fn item_color(kind: ItemKind) -> Color {
+    match kind {
+        ItemKind::Berry => RED,
+        ItemKind::Apple => Color::new(0.85, 0.1, 0.1, 1.0),
+        ItemKind::Mushroom => Color::new(0.8, 0.3, 0.2, 1.0),
+        ItemKind::Stick => BROWN,
+        ItemKind::Feather => WHITE,
+        ItemKind::Medicine => PINK,
+    }
+}
+
+// Draw one item lying in the world, centred on pos and turned by spin radians.
+fn draw_item(kind: ItemKind, pos: Vec3, spin: f32) {
+    let color = item_color(kind);
+    let along = vec3(spin.cos(), 0.0, spin.sin());
+    match kind {
+        ItemKind::Berry => draw_sphere(pos, 0.15, None, color),
+        // Apples have a little green leaf on top.
+        ItemKind::Apple => {
+            draw_sphere(pos, 0.3, None, color);
+            draw_cube(pos + vec3(0.05, 0.32, 0.0), vec3(0.15, 0.05, 0.08), None, GREEN);
+        }
+        // Mushrooms are a pale stem under a spotted cap.
+        ItemKind::Mushroom => {
+            draw_cube(pos - vec3(0.0, 0.1, 0.0), vec3(0.12, 0.3, 0.12), None, BEIGE);
+            draw_sphere(pos + vec3(0.0, 0.1, 0.0), 0.25, None, color);
+            draw_sphere(pos + vec3(0.1, 0.3, 0.05), 0.05, None, WHITE);
+        }
+        // Sticks and feathers are long and thin, so spin them around.
+        ItemKind::Stick => {
+            for step in [-0.4, -0.2, 0.0, 0.2, 0.4] {
+                draw_sphere(pos + along * step, 0.07, None, color);
+            }
+        }
+        ItemKind::Feather => {
+            for (step, size) in [(-0.3, 0.05), (-0.15, 0.1), (0.0, 0.12), (0.15, 0.1), (0.3, 0.06)] {
+                draw_sphere(pos + along * step, size, None, color);
+            }
+        }
+        // Medicine is a small pink box with a white cross.
+        ItemKind::Medicine => {
+            draw_cube(pos, vec3(0.35, 0.25, 0.35), None, color);
+            draw_cube(pos + vec3(0.0, 0.13, 0.0), vec3(0.25, 0.02, 0.08), None, WHITE);
+            draw_cube(pos + vec3(0.0, 0.13, 0.0), vec3(0.08, 0.02, 0.25), None, WHITE);
+        }
+    }
+}
+
+// Draw the hotbar: one slot per carried stack along the bottom of the screen, with the selected
+// one highlighted and its name written above it.
+fn draw_hotbar(inventory: &Inventory) {
+    const SLOT: f32 = 44.0;
+    const GAP: f32 = 6.0;
+    let count = inventory.stacks.len().max(1) as f32;
+    let left = screen_width() / 2.0 - (count * (SLOT + GAP) - GAP) / 2.0;
+    let top = screen_height() - 140.0;
+    if inventory.stacks.is_empty() {
+        draw_rectangle_lines(left, top, SLOT, SLOT, 2.0, GRAY);
+        draw_text("Empty", left + 2.0, top - 8.0, 20.0, DARKGRAY);
+        return;
+    }
+    for (index, stack) in inventory.stacks.iter().enumerate() {
+        let x = left + index as f32 * (SLOT + GAP);
+        let selected = index == inventory.selected;
+        draw_rectangle(x, top, SLOT, SLOT, Color::new(0.0, 0.0, 0.0, 0.3));
+        draw_circle(x + SLOT / 2.0, top + SLOT / 2.0, SLOT * 0.3, item_color(stack.kind));
+        draw_text(&stack.count.to_string(), x + SLOT - 14.0, top + SLOT - 4.0, 20.0, BLACK);
+        if selected {
+            draw_rectangle_lines(x, top, SLOT, SLOT, 3.0, YELLOW);
+            draw_text(stack.kind.name(), x, top - 8.0, 20.0, BLACK);
+        } else {
+            draw_rectangle_lines(x, top, SLOT, SLOT, 1.0, GRAY);
+        }
+    }
+}
+
+// Draw the Heads-Up Display (HUD) in screen space.
+// With a gamepad plugged in, the controls shown are the pad's.
+pub fn draw_hud(world: &World, gamepad: bool) {
+    let pet = &world.pet;
+
+    // Display the pet's name.
+    draw_text(&format!("Name: {}", pet.name), 20.0, 30.0, 30.0, BLACK);
+
+    // Show the current survival time if the pet is alive.
+    if pet.is_alive {
+        let survival_time = pet.survival_time(world.time);
+        draw_text(&format!("Survived: {:.1}s", survival_time), 20.0, 50.0, 20.0, DARKGRAY);
+        let label = world.config.difficulty.get(world.difficulty).label(world.difficulty);
+        draw_text(&label, 20.0, 70.0, 20.0, RED);
+    }
+    // The number of bugs squashed so far.
+    draw_text(&format!("Bugs squashed: {}", world.kills), screen_width() - 220.0, 50.0, 20.0, DARKGRAY);
+    // The run seed, so an interesting run can be shared and replayed.
+    draw_text(&format!("Seed: {}", world.seed), screen_width() - 220.0, 30.0, 20.0, DARKGRAY);
+    // The in-game clock.
+    let hour = world.hour();
+    let day = daycycle::day_number(world.time, &world.config.day);
+    let clock = format!("Day {}, {:02}:{:02}", day, hour as u32, (hour.fract() * 60.0) as u32);
+    draw_text(&clock, screen_width() - 220.0, 70.0, 20.0, DARKGRAY);
+    // The weather, in a colour that matches it.
+    let weather = world.weather.kind;
+    let weather_color = match weather {
+        WeatherKind::Clear => DARKGRAY,
+        WeatherKind::Rain => DARKBLUE,
+        WeatherKind::Wind => GRAY,
+        WeatherKind::Heat => ORANGE,
+    };
+    draw_text(&format!("Weather: {}", weather.name()), screen_width() - 220.0, 90.0, 20.0, weather_color);
+
+    // Draw the status bars for Hunger, Happiness, and Energy.
+    draw_bar(80.0, "Hunger", pet.hunger, RED);
+    draw_bar(110.0, "Happiness", pet.happiness, GREEN);
+    draw_bar(140.0, "Energy", pet.energy, BLUE);
+    // The health bar flashes while the pet is invulnerable after a bite.
+    let flash = pet.invulnerable > 0.0 && (get_time() * 10.0) as i64 % 2 == 0;
+    draw_bar(170.0, "Health", pet.health, if flash { WHITE } else { MAROON });
+    // Next to the energy it uses up, whether the pet is sprinting.
+    if pet.sprinting {
+        draw_text("Sprinting!", 330.0, 155.0, 20.0, BLUE);
+    }
+    // The pet's mood, or that it is asleep.
+    let mood = if pet.asleep { "Asleep" } else { pet.mood.name() };
+    draw_text(&format!("Mood: {}", mood), 20.0, 215.0, 20.0, DARKGRAY);
+    // The pet's life stage, and the form it grew into.
+    let stage = match pet.form {
+        Some(form) => format!("Stage: {} {} ({})", pet.stage.name(), pet.species, form.name()),
+        None => format!("Stage: {} {}", pet.stage.name(), pet.species),
+    };
+    draw_text(&stage, 20.0, 235.0, 20.0, DARKGRAY);
+    // The fetch streak, and the power of a throw while it is wound up.
+    if world.fetch.streak > 0 {
+        draw_text(&format!("Fetch streak: {}", world.fetch.streak), 20.0, 255.0, 20.0, DARKGRAY);
+    }
+    if world.fetch.charge > 0.0 {
+        draw_bar(270.0, "Throw", world.fetch.power(&world.config.fetch) * 100.0, ORANGE);
+    }
+    // The dash fills back up after use, and is ready once the bar is full.
+    let cooldown = world.config.sprint.dash_cooldown;
+    let dash_ready = (100.0 * (1.0 - pet.dash_cooldown / cooldown)).clamp(0.0, 100.0);
+    draw_bar(300.0, "Dash", dash_ready, if pet.dash_cooldown > 0.0 { GRAY } else { SKYBLUE });
+
+    if !pet.is_alive {
+        // The survival timer is frozen at the moment of death.
+        let final_survival = pet.survival_time(world.time);
+        draw_text("GAME OVER", screen_width() / 2.0 - 100.0, screen_height() / 2.0 - 40.0, 50.0, RED);
+        if let Some(cause) = pet.death_cause {
+            draw_text(&format!("{} {}.", pet.name, cause.description()), screen_width() / 2.0 - 120.0, screen_height() / 2.0 - 10.0, 25.0, BLACK);
+        }
+        draw_text(&format!("You survived for {:.2} seconds!", final_survival), screen_width() / 2.0 - 120.0, screen_height() / 2.0 + 15.0, 25.0, BLACK);
+        draw_text(&format!("Bugs squashed: {}", world.kills), screen_width() / 2.0 - 120.0, screen_height() / 2.0 + 40.0, 20.0, BLACK);
+        draw_text("Press R to restart", screen_width() / 2.0 - 80.0, screen_height() / 2.0 + 65.0, 20.0, DARKGRAY);
+        if gamepad {
+            draw_text(&format!("or Start to try again with {}", pet.name), screen_width() / 2.0 - 80.0, screen_height() / 2.0 + 85.0, 20.0, DARKGRAY);
+        }
+    } else {
+        // If the pet is alive, display the system message and controls.
+        draw_hotbar(&pet.inventory);
+        draw_text(&world.message, 20.0, screen_height() - 60.0, 25.0, DARKGRAY);
+        if gamepad {
+            draw_text("Left stick: Move | Right stick: Camera | A: Jump | X: Feed | Y: Play | B: Sleep | LB: Sprint | RB: Dash | Back: POV", 20.0, screen_height() - 30.0, 20.0, BLACK);
+            return;
+        }
+        draw_text("1: Feed | 2: Play | 3: Sleep | Tab/C: Next/Use item | F: Pounce | E: Eat bug | G: Forage | T: Pick up/Throw ball | Space: Jump | Shift: Sprint | Q: Dash | WASD: Move/Turn | V: POV | F5: Save", 20.0, screen_height() - 30.0, 20.0, BLACK);
+    }
+}
@@ -0,0 +1,201 @@
+// Recording and playing back runs.
+// A replay file holds the world as it was when the run started, the configuration it ran with,
+// and the input of every tick.
+// Because the World is fully deterministic, stepping the same start with the same inputs
+// reproduces the run exactly, including the bug spawns and the moment of death.
+use std::fs;
+
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::input::InputState;
+use crate::save::{self, SaveError};
+use crate::world::World;
+
+// The version of the replay format written by this build.
+pub const REPLAY_VERSION: u32 = 25;
+
+// Bit flags used to pack the boolean parts of an InputState into a single number.
+const FORWARD: u32 = 1 << 0;
+const BACK: u32 = 1 << 1;
+const LEFT: u32 = 1 << 2;
+const RIGHT: u32 = 1 << 3;
+const FIRST_PERSON: u32 = 1 << 4;
+const FEED: u32 = 1 << 5;
+const PLAY: u32 = 1 << 6;
+const SLEEP: u32 = 1 << 7;
+const POUNCE: u32 = 1 << 8;
+const EAT: u32 = 1 << 9;
+const GATHER: u32 = 1 << 10;
+const NEXT_ITEM: u32 = 1 << 11;
+const USE_ITEM: u32 = 1 << 12;
+const THROW: u32 = 1 << 13;
+const JUMP: u32 = 1 << 14;
+const SPRINT: u32 = 1 << 15;
+const DASH: u32 = 1 << 16;
+
+// The input of one tick in a compact form: the flags above, the mouse look delta and the gamepad stick.
+// In the JSON file every tick is just a short array like [17, 0.0, 0.0, 0.0, 0.0].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickRecord(u32, f32, f32, f32, f32);
+
+impl From<&InputState> for TickRecord {
+    fn from(input: &InputState) -> TickRecord {
+        let flags = [
+            (input.forward, FORWARD),
+            (input.back, BACK),
+            (input.left, LEFT),
+            (input.right, RIGHT),
+            (input.first_person, FIRST_PERSON),
+            (input.feed, FEED),
+            (input.play, PLAY),
+            (input.sleep, SLEEP),
+            (input.pounce, POUNCE),
+            (input.eat, EAT),
+            (input.gather, GATHER),
+            (input.next_item, NEXT_ITEM),
+            (input.use_item, USE_ITEM),
+            (input.throw, THROW),
+            (input.jump, JUMP),
+            (input.sprint, SPRINT),
+            (input.dash, DASH),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .fold(0, |acc, (_, bit)| acc | bit);
+        TickRecord(flags, input.look_delta.x, input.look_delta.y, input.stick.x, input.stick.y)
+    }
+}
+
+impl From<&TickRecord> for InputState {
+    fn from(record: &TickRecord) -> InputState {
+        let TickRecord(flags, look_x, look_y, stick_x, stick_y) = *record;
+        InputState {
+            forward: flags & FORWARD != 0,
+            back: flags & BACK != 0,
+            left: flags & LEFT != 0,
+            right: flags & RIGHT != 0,
+            stick: Vec2::new(stick_x, stick_y),
+            look_delta: Vec2::new(look_x, look_y),
+            first_person: flags & FIRST_PERSON != 0,
+            feed: flags & FEED != 0,
+            play: flags & PLAY != 0,
+            sleep: flags & SLEEP != 0,
+            pounce: flags & POUNCE != 0,
+            eat: flags & EAT != 0,
+            gather: flags & GATHER != 0,
+            next_item: flags & NEXT_ITEM != 0,
+            use_item: flags & USE_ITEM != 0,
+            throw: flags & THROW != 0,
+            jump: flags & JUMP != 0,
+            sprint: flags & SPRINT != 0,
+            dash: flags & DASH != 0,
+        }
+    }
+}
+
+// The layout of a replay file on disk.
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    // The world exactly as it was before the first tick.
+    pub start: World,
+    // The configuration the run was played with, which the world itself does not save.
+    pub config: Config,
+    // The input of every tick, in order.
+    pub ticks: Vec<TickRecord>,
+}
+
+impl Replay {
+    // Read a replay file from the given path.
+    pub fn load(path: &str) -> Result<Replay, SaveError> {
+        let json = fs::read_to_string(path)?;
+        let version = save::read_version(&json)?;
+        if version != REPLAY_VERSION {
+            return Err(SaveError::Version(version));
+        }
+        let mut replay: Replay = serde_json::from_str(&json)?;
+        replay.start.config = replay.config.clone();
+        replay.start.prepare_chunks();
+        Ok(replay)
+    }
+}
+
+// The file the recording of the given run (counting from 1) is written to, so a restart does not
+// overwrite the replay of the run before it: "run.json" for the first run, then "run-2.json", "run-3.json"...
+pub fn run_path(path: &str, run: u32) -> String {
+    if run <= 1 {
+        return path.to_string();
+    }
+    let name_start = path.rfind(['/', '\\']).map_or(0, |i| i + 1);
+    match path[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => format!("{}-{}{}", &path[..name_start + dot], run, &path[name_start + dot..]),
+        _ => format!("{}-{}", path, run),
+    }
+}
+
+// Records the input of a run and writes it out once the run is over.
+pub struct Recorder {
+    // Where the replay file is written.
+    path: String,
+    // The recording so far.
+    replay: Replay,
+    // Set once the file has been written, so a run is only written once.
+    finished: bool,
+}
+
+impl Recorder {
+    // Start recording a run that begins with the given world.
+    pub fn new(path: String, start: &World) -> Recorder {
+        Recorder {
+            path,
+            replay: Replay { version: REPLAY_VERSION, start: start.clone(), config: start.config.clone(), ticks: Vec::new() },
+            finished: false,
+        }
+    }
+
+    // Remember the input used for one tick.
+    pub fn record(&mut self, input: &InputState) {
+        if !self.finished {
+            self.replay.ticks.push(TickRecord::from(input));
+        }
+    }
+
+    // Write the recording to disk. Calling this again after it succeeded does nothing.
+    pub fn finish(&mut self) -> Result<(), SaveError> {
+        if self.finished {
+            return Ok(());
+        }
+        fs::write(&self.path, serde_json::to_string(&self.replay)?)?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+// Feeds the recorded input of a replay back into the simulation, one tick at a time.
+pub struct Player {
+    ticks: Vec<TickRecord>,
+    // Index of the next tick to play.
+    next: usize,
+}
+
+impl Player {
+    pub fn new(ticks: Vec<TickRecord>) -> Player {
+        Player { ticks, next: 0 }
+    }
+
+    // The input for the next tick, or None once the recording has run out.
+    pub fn next_input(&mut self) -> Option<InputState> {
+        let input = self.ticks.get(self.next).map(InputState::from);
+        if input.is_some() {
+            self.next += 1;
+        }
+        input
+    }
+
+    // Start playing from the first tick again.
+    pub fn rewind(&mut self) {
+        self.next = 0;
+    }
+}
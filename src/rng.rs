@@ -0,0 +1,37 @@
+// A tiny deterministic random number generator.
+// Every run owns one of these, created from the run seed, so the same seed and the same
+// inputs always produce exactly the same game.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rng {
+    // The internal state. It is saved with the world so a continued game stays reproducible.
+    state: u64,
+}
+
+impl Rng {
+    // Create a generator from a seed. Equal seeds give equal sequences.
+    pub fn new(seed: u64) -> Rng {
+        let mut rng = Rng { state: seed };
+        // Mix the seed once so that small seeds like 1 and 2 do not start out looking alike.
+        rng.next_u32();
+        rng
+    }
+
+    // A 64-bit Linear Congruential Generator (LCG), returning the high 32 bits of the state.
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.state >> 32) as u32
+    }
+
+    // A random integer in the range low..high (high is excluded).
+    pub fn range_u32(&mut self, low: u32, high: u32) -> u32 {
+        low + self.next_u32() % (high - low).max(1)
+    }
+}
+
+// Pick a fresh seed from the system clock for runs that were not given one.
+pub fn random_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0)
+}
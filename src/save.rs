@@ -0,0 +1,106 @@
+// Saving and loading the whole World to a JSON file on disk.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::world::World;
+
+// Where the game is saved, relative to the directory the game was started from.
+pub const SAVE_PATH: &str = "savegame.json";
+
+// The version of the save format written by this build.
+// Bump this whenever a change to the saved structs would break older files.
+pub const SAVE_VERSION: u32 = 2;
+
+// Everything that can go wrong while reading or writing a save file.
+#[derive(Debug)]
+pub enum SaveError {
+    // The file could not be read or written.
+    Io(io::Error),
+    // The file exists but is not valid JSON for our save format.
+    Format(serde_json::Error),
+    // The file was written by a build with a file format we do not understand.
+    Version(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "could not access save file: {}", e),
+            SaveError::Format(e) => write!(f, "save file is corrupted: {}", e),
+            SaveError::Version(v) => write!(f, "file format version {} is not supported by this build", v),
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> SaveError {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> SaveError {
+        SaveError::Format(e)
+    }
+}
+
+// The layout of the file on disk: a version number, when it was written, and the world itself.
+#[derive(Serialize, Deserialize)]
+struct SaveFile<W> {
+    version: u32,
+    // Wall-clock time of the save in seconds since the Unix epoch.
+    // Files written before this field existed load with 0, meaning "unknown".
+    #[serde(default)]
+    saved_at: u64,
+    world: W,
+}
+
+// The current wall-clock time in seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+// Only the version is read first, so an unknown version is reported as such
+// instead of as a confusing parse error.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+// Read just the "version" field of a JSON save or replay file.
+pub fn read_version(json: &str) -> Result<u32, SaveError> {
+    let header: SaveHeader = serde_json::from_str(json)?;
+    Ok(header.version)
+}
+
+// Write the world to the given path, replacing any previous save.
+pub fn save_world(world: &World, path: &str) -> Result<(), SaveError> {
+    let file = SaveFile { version: SAVE_VERSION, saved_at: unix_now(), world };
+    let json = serde_json::to_string_pretty(&file)?;
+    // Write to a temporary file first and then rename it over the old save,
+    // so a crash halfway through writing can never leave a broken save behind.
+    let tmp_path = format!("{}.tmp", path);
+    fs::write(&tmp_path, json)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+// Read a previously saved world from the given path, to run with the given configuration.
+// Also returns the Unix time the save was written at, or 0 if the file does not say.
+// Saves from before the configuration was left out still have it in them, and it is ignored.
+pub fn load_world(path: &str, config: &Config) -> Result<(World, u64), SaveError> {
+    let json = fs::read_to_string(path)?;
+    let version = read_version(&json)?;
+    if version != SAVE_VERSION {
+        return Err(SaveError::Version(version));
+    }
+    let mut file: SaveFile<World> = serde_json::from_str(&json)?;
+    file.world.config = config.clone();
+    file.world.prepare_chunks();
+    Ok((file.world, file.saved_at))
+}
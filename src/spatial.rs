@@ -0,0 +1,58 @@
+// A uniform grid that buckets points by position, so "what is near here?" only has to look
+// at a few cells instead of at every bug in the world.
+use rustc_hash::FxHashMap;
+
+// Side length of one grid cell in world units.
+pub const CELL_SIZE: f32 = 8.0;
+
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    // Side length of one cell.
+    cell_size: f32,
+    // The indices of the points in each non-empty cell, keyed by cell coordinate.
+    cells: FxHashMap<(i32, i32), Vec<usize>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> SpatialGrid {
+        SpatialGrid::new(CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> SpatialGrid {
+        SpatialGrid { cell_size, cells: FxHashMap::default() }
+    }
+
+    // The cell a world position falls into.
+    fn cell_of(&self, x: f32, z: f32) -> (i32, i32) {
+        ((x / self.cell_size).floor() as i32, (z / self.cell_size).floor() as i32)
+    }
+
+    // Throw away the old contents and insert every point again.
+    // The index of each point in the iterator is what queries return.
+    pub fn rebuild(&mut self, points: impl Iterator<Item = (f32, f32)>) {
+        self.cells.clear();
+        for (i, (x, z)) in points.enumerate() {
+            let cell = self.cell_of(x, z);
+            self.cells.entry(cell).or_default().push(i);
+        }
+    }
+
+    // The indices of all points in the cells touching the square around (x, z).
+    // This may include points slightly further than `radius`, so callers still check the exact distance.
+    pub fn query(&self, x: f32, z: f32, radius: f32) -> Vec<usize> {
+        self.nearby(x, z, radius).collect()
+    }
+
+    // Like query, but hands the indices out one at a time, so a caller that only needs
+    // a few of them can stop early without copying crowded cells.
+    pub fn nearby(&self, x: f32, z: f32, radius: f32) -> impl Iterator<Item = usize> + '_ {
+        let (min_x, min_z) = self.cell_of(x - radius, z - radius);
+        let (max_x, max_z) = self.cell_of(x + radius, z + radius);
+        (min_x..=max_x)
+            .flat_map(move |cx| (min_z..=max_z).map(move |cz| (cx, cz)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flat_map(|indices| indices.iter().copied())
+    }
+}
@@ -0,0 +1,55 @@
+// The special abilities a pet species can have. Which species has which ability, and everything
+// else about a species, is defined in the [[species]] tables of the configuration.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ability {
+    #[default]
+    None,
+    // Squashed bugs fill it up twice as much.
+    Glutton,
+    // Pounces further, for half the energy.
+    Hunter,
+    // Reaches flowers and puddles from twice as far.
+    Forager,
+    // Bug bites do half the damage.
+    Shell,
+}
+
+impl Ability {
+    // The description shown on the start screen.
+    pub fn description(self) -> &'static str {
+        match self {
+            Ability::None => "No special ability.",
+            Ability::Glutton => "Glutton: squashed bugs fill it up twice as much.",
+            Ability::Hunter => "Hunter: pounces further for half the energy.",
+            Ability::Forager => "Forager: reaches flowers and puddles from twice as far.",
+            Ability::Shell => "Shell: bug bites do half the damage.",
+        }
+    }
+
+    // Factor on the hunger taken away by eating a squashed bug.
+    pub fn bug_nutrition(self) -> f32 {
+        if self == Ability::Glutton { 2.0 } else { 1.0 }
+    }
+
+    // Factors on the pounce radius and on its energy cost.
+    pub fn pounce_reach(self) -> f32 {
+        if self == Ability::Hunter { 1.5 } else { 1.0 }
+    }
+
+    pub fn pounce_cost(self) -> f32 {
+        if self == Ability::Hunter { 0.5 } else { 1.0 }
+    }
+
+    // Factor on how far away the pet can forage.
+    pub fn forage_reach(self) -> f32 {
+        if self == Ability::Forager { 2.0 } else { 1.0 }
+    }
+
+    // Factor on the damage of bug bites.
+    pub fn bite_damage(self) -> f32 {
+        if self == Ability::Shell { 0.5 } else { 1.0 }
+    }
+}
@@ -0,0 +1,298 @@
+// The shape of the ground, its biomes, and the features scattered over it (bushes, trees,
+// berry flowers, puddles and cacti) with their colliders.
+// The height and the biomes come from smooth value noise, and every chunk of the world gets its
+// features from a small LCG seeded with the chunk coordinates and the run seed, so a chunk
+// always looks the same and the same seed gives the same world.
+use macroquad::math::Vec2;
+
+// The height of the water surface in ponds. Ground below it is under water.
+pub const WATER_LEVEL: f32 = -2.2;
+// How far the ground rises and falls around 0 in hilly areas.
+const HILL_HEIGHT: f32 = 6.0;
+// How deep the ground sinks in the middle of a pond.
+const POND_DEPTH: f32 = 4.5;
+// The size of a hill, and of a biome region, in world units.
+const HILL_SCALE: f32 = 50.0;
+const MOISTURE_SCALE: f32 = 160.0;
+const HEAT_SCALE: f32 = 220.0;
+// Moisture above which the ground sinks into a pond, reaching full depth POND_RAMP later.
+const POND_MOISTURE: f32 = 0.66;
+const POND_RAMP: f32 = 0.06;
+
+// The kinds of region the world is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Biome {
+    // Open grass with flowers and bushes.
+    Meadow,
+    // Dense trees.
+    Forest,
+    // Flat, dry sand with cacti and the odd puddle.
+    Desert,
+    // A hollow filled with water, with reeds around it.
+    Pond,
+}
+
+impl Biome {
+    // How many features a chunk of this biome has: at least the first value, below the second.
+    fn feature_count(self) -> (u32, u32) {
+        match self {
+            Biome::Meadow => (2, 7),
+            Biome::Forest => (6, 13),
+            Biome::Desert => (0, 4),
+            Biome::Pond => (1, 5),
+        }
+    }
+
+    // The relative chances of each feature kind, in the order of FeatureKind::ALL.
+    fn feature_weights(self) -> [u32; 5] {
+        match self {
+            Biome::Meadow => [3, 1, 4, 1, 0],
+            Biome::Forest => [2, 6, 1, 1, 0],
+            Biome::Desert => [0, 0, 0, 2, 3],
+            Biome::Pond => [1, 0, 3, 1, 0],
+        }
+    }
+}
+
+// The kinds of features a chunk can contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureKind {
+    // A big grassy bush. Soft enough to walk through.
+    Bush,
+    // A tree with a solid trunk.
+    Tree,
+    // A small flower on a stem, carrying a red berry the pet can pick.
+    Flower,
+    // A small puddle of fresh water the pet can drink from.
+    Puddle,
+    // A prickly desert cactus that blocks the way.
+    Cactus,
+}
+
+impl FeatureKind {
+    // Every kind of feature, in the order used by the biome weights.
+    pub const ALL: [FeatureKind; 5] = [FeatureKind::Bush, FeatureKind::Tree, FeatureKind::Flower, FeatureKind::Puddle, FeatureKind::Cactus];
+}
+
+// One feature placed in the world.
+#[derive(Debug, Clone)]
+pub struct Feature {
+    pub kind: FeatureKind,
+    pub x: f32,
+    // The height of the ground the feature stands on.
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Feature {
+    // The solid part of the feature, if it has one.
+    pub fn collider(&self) -> Option<Collider> {
+        match self.kind {
+            // The trunk is a 0.5 x 0.5 square column, with the leaves on top.
+            FeatureKind::Tree => Some(Collider::Box { x: self.x, z: self.z, half_size: 0.25, top: self.y + 3.2 }),
+            FeatureKind::Cactus => Some(Collider::Circle { x: self.x, z: self.z, radius: 0.3, top: self.y + 1.8 }),
+            FeatureKind::Bush | FeatureKind::Flower | FeatureKind::Puddle => None,
+        }
+    }
+}
+
+// The shape of a solid feature on the ground plane.
+#[derive(Debug, Clone, Copy)]
+pub enum Collider {
+    // A round obstacle, like a cactus.
+    Circle { x: f32, z: f32, radius: f32, top: f32 },
+    // A square obstacle lined up with the axes, like a tree trunk.
+    // top is the height of the top of the obstacle, so a jumping pet can clear the low ones.
+    Box { x: f32, z: f32, half_size: f32, top: f32 },
+}
+
+impl Collider {
+    // The middle of the collider.
+    pub fn center(&self) -> Vec2 {
+        match *self {
+            Collider::Circle { x, z, .. } | Collider::Box { x, z, .. } => Vec2::new(x, z),
+        }
+    }
+
+    // The height of the top of the collider.
+    pub fn top(&self) -> f32 {
+        match *self {
+            Collider::Circle { top, .. } | Collider::Box { top, .. } => top,
+        }
+    }
+
+    // The radius of a circle around the center that contains the whole collider.
+    pub fn bounding_radius(&self) -> f32 {
+        match *self {
+            Collider::Circle { radius, .. } => radius,
+            Collider::Box { half_size, .. } => half_size * std::f32::consts::SQRT_2,
+        }
+    }
+
+    // If a circle of the given radius at `pos` overlaps the collider, returns the direction
+    // to push the circle out (a unit vector) and how far it has to move.
+    pub fn penetration(&self, pos: Vec2, radius: f32) -> Option<(Vec2, f32)> {
+        match *self {
+            Collider::Circle { x, z, radius: own, .. } => {
+                let offset = pos - Vec2::new(x, z);
+                let dist = offset.length();
+                if dist >= radius + own {
+                    return None;
+                }
+                // Exactly on the center: any direction will do.
+                let normal = if dist > 0.0001 { offset / dist } else { Vec2::X };
+                Some((normal, radius + own - dist))
+            }
+            Collider::Box { x, z, half_size, .. } => {
+                let offset = pos - Vec2::new(x, z);
+                let closest = offset.clamp(Vec2::splat(-half_size), Vec2::splat(half_size));
+                if closest != offset {
+                    // Outside the box: push away from the closest point on its edge.
+                    let to_pos = offset - closest;
+                    let dist = to_pos.length();
+                    if dist >= radius {
+                        return None;
+                    }
+                    return Some((to_pos / dist, radius - dist));
+                }
+                // The center is inside the box: push out along the axis with the shortest way out.
+                let depth_x = half_size - offset.x.abs();
+                let depth_z = half_size - offset.y.abs();
+                if depth_x < depth_z {
+                    Some((Vec2::new(offset.x.signum(), 0.0), depth_x + radius))
+                } else {
+                    Some((Vec2::new(0.0, offset.y.signum()), depth_z + radius))
+                }
+            }
+        }
+    }
+}
+
+// Move a circle out of every collider it overlaps.
+// Returns the direction it was last pushed in, or None if it was not touching anything.
+pub fn push_out(pos: &mut Vec2, radius: f32, colliders: &[Collider]) -> Option<Vec2> {
+    let mut pushed = None;
+    for collider in colliders {
+        if let Some((normal, depth)) = collider.penetration(*pos, radius) {
+            *pos += normal * depth;
+            pushed = Some(normal);
+        }
+    }
+    pushed
+}
+
+// A random value between 0 and 1 for every whole-number grid point, the same for the same seed.
+fn hash(seed: u32, ix: i32, iz: i32) -> f32 {
+    let mut h = seed ^ (ix as u32).wrapping_mul(0x27d4_eb2d) ^ (iz as u32).wrapping_mul(0x1656_67b1);
+    h = (h ^ (h >> 15)).wrapping_mul(0x85eb_ca6b);
+    h = (h ^ (h >> 13)).wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    (h >> 8) as f32 / (1 << 24) as f32
+}
+
+// Value noise: the random grid values, blended smoothly in between. Always between 0 and 1.
+fn value_noise(seed: u32, x: f32, z: f32) -> f32 {
+    let (ix, iz) = (x.floor() as i32, z.floor() as i32);
+    let (fx, fz) = (x - x.floor(), z - z.floor());
+    // Smoothstep, so the slopes join up without creases at the grid lines.
+    let (sx, sz) = (fx * fx * (3.0 - 2.0 * fx), fz * fz * (3.0 - 2.0 * fz));
+    let top = hash(seed, ix, iz) + (hash(seed, ix + 1, iz) - hash(seed, ix, iz)) * sx;
+    let bottom = hash(seed, ix, iz + 1) + (hash(seed, ix + 1, iz + 1) - hash(seed, ix, iz + 1)) * sx;
+    top + (bottom - top) * sz
+}
+
+// Three layers of value noise, each half as strong and twice as detailed as the one before.
+// `scale` is the size of the biggest bumps in world units. Always between 0 and 1.
+fn fractal_noise(seed: u32, x: f32, z: f32, scale: f32) -> f32 {
+    let mut total = 0.0;
+    let mut strength = 0.5;
+    let mut frequency = 1.0 / scale;
+    for octave in 0..3 {
+        total += value_noise(seed.wrapping_add(octave), x * frequency, z * frequency) * strength;
+        strength *= 0.5;
+        frequency *= 2.0;
+    }
+    total / 0.875
+}
+
+// A separate noise seed for each layer (height, moisture, heat), derived from the run seed.
+fn layer_seed(world_seed: u64, layer: u32) -> u32 {
+    ((world_seed ^ (world_seed >> 32)) as u32).wrapping_add(layer.wrapping_mul(0x9e37_79b9))
+}
+
+fn moisture(world_seed: u64, x: f32, z: f32) -> f32 {
+    fractal_noise(layer_seed(world_seed, 1), x, z, MOISTURE_SCALE)
+}
+
+// The biome at a world position, decided by how wet and how hot the area is.
+pub fn biome_at(world_seed: u64, x: f32, z: f32) -> Biome {
+    let wet = moisture(world_seed, x, z);
+    let heat = fractal_noise(layer_seed(world_seed, 2), x, z, HEAT_SCALE);
+    if wet > POND_MOISTURE {
+        Biome::Pond
+    } else if heat > 0.55 && wet < 0.45 {
+        Biome::Desert
+    } else if wet > 0.5 {
+        Biome::Forest
+    } else {
+        Biome::Meadow
+    }
+}
+
+// The height of the ground at a world position, which may be under water.
+pub fn terrain_height(world_seed: u64, x: f32, z: f32) -> f32 {
+    let hills = (fractal_noise(layer_seed(world_seed, 0), x, z, HILL_SCALE) - 0.5) * HILL_HEIGHT;
+    // Ponds sink smoothly into the ground as the area gets wetter.
+    let pond = ((moisture(world_seed, x, z) - POND_MOISTURE) / POND_RAMP).clamp(0.0, 1.0);
+    let pond = pond * pond * (3.0 - 2.0 * pond);
+    hills - pond * POND_DEPTH
+}
+
+// The height things stand on at a world position: the ground, or the water surface above it.
+pub fn ground_height(world_seed: u64, x: f32, z: f32) -> f32 {
+    terrain_height(world_seed, x, z).max(WATER_LEVEL)
+}
+
+// Generate the features of one chunk of the given size.
+// How many there are and which kinds depends on the biome in the middle of the chunk.
+// The same run seed and chunk coordinates always give the same features.
+pub fn generate_chunk(world_seed: u64, cx: i32, cz: i32, chunk_size: f32) -> Vec<Feature> {
+    // Calculate world coordinates for the corner of the chunk.
+    let x = cx as f32 * chunk_size;
+    let z = cz as f32 * chunk_size;
+    let biome = biome_at(world_seed, x + chunk_size / 2.0, z + chunk_size / 2.0);
+
+    // Deterministic Randomization: generate a unique "seed" for this specific chunk.
+    // The run seed is mixed in so every run gets its own world layout.
+    let mut rng = (cx.wrapping_mul(73856093) ^ cz.wrapping_mul(19349663)) as u32 ^ (world_seed ^ (world_seed >> 32)) as u32;
+    // A simple Linear Congruential Generator (LCG) for randomness.
+    let mut next_rng = || {
+        rng = rng.wrapping_mul(1103515245).wrapping_add(12345);
+        (rng >> 16) & 0x7fff
+    };
+
+    let (min_count, max_count) = biome.feature_count();
+    let num_features = min_count + next_rng() % (max_count - min_count);
+    let weights = biome.feature_weights();
+    let total: u32 = weights.iter().sum();
+    (0..num_features)
+        .filter_map(|_| {
+            // Randomly position the feature within the chunk.
+            let fx = x + (next_rng() % 100) as f32 / 100.0 * chunk_size;
+            let fz = z + (next_rng() % 100) as f32 / 100.0 * chunk_size;
+            // Randomly decide which type of feature it is, using the biome's weights.
+            let mut roll = next_rng() % total;
+            let index = weights.iter().position(|&weight| {
+                let hit = roll < weight;
+                roll = roll.saturating_sub(weight);
+                hit
+            })?;
+            // Nothing grows under water.
+            let fy = terrain_height(world_seed, fx, fz);
+            if fy < WATER_LEVEL {
+                return None;
+            }
+            Some(Feature { kind: FeatureKind::ALL[index], x: fx, y: fy, z: fz })
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+// Weather that rolls through the world: clear skies, rain, wind and heat waves, one after another.
+// Each spell of weather fades in, lasts a random time and fades out again, and while it lasts it
+// changes how the pet's stats drain and how the pet and the ball slide over the ground.
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, WeatherConfig};
+use crate::rng::Rng;
+
+// Seconds a spell of weather takes to fade in at its start and out at its end.
+const FADE_TIME: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeatherKind {
+    Clear,
+    // Makes the pet sad and the ground slippery.
+    Rain,
+    // Pushes the ball around.
+    Wind,
+    // Makes the pet hungry and tired faster.
+    Heat,
+}
+
+impl WeatherKind {
+    // Every kind of weather, in the order used by the weights.
+    pub const ALL: [WeatherKind; 4] = [WeatherKind::Clear, WeatherKind::Rain, WeatherKind::Wind, WeatherKind::Heat];
+
+    // The name shown on the HUD.
+    pub fn name(self) -> &'static str {
+        match self {
+            WeatherKind::Clear => "Clear",
+            WeatherKind::Rain => "Rain",
+            WeatherKind::Wind => "Windy",
+            WeatherKind::Heat => "Heat wave",
+        }
+    }
+
+    // The message shown when this weather arrives.
+    pub fn announcement(self) -> &'static str {
+        match self {
+            WeatherKind::Clear => "The sky clears up.",
+            WeatherKind::Rain => "It started to rain. The ground is getting slippery...",
+            WeatherKind::Wind => "The wind is picking up.",
+            WeatherKind::Heat => "A heat wave rolls in. Keep food close!",
+        }
+    }
+}
+
+// How the current weather changes the simulation this tick.
+#[derive(Debug, Clone)]
+pub struct WeatherEffects {
+    // Fraction of velocity the pet and the ball keep every 60Hz frame.
+    pub friction: f32,
+    // Extra happiness lost every second.
+    pub happiness_loss: f32,
+    // Hunger and energy drain are multiplied by this.
+    pub drain: f32,
+    // Velocity the wind adds to the ball every 60Hz frame.
+    pub wind: Vec2,
+}
+
+// The weather right now and how far along it is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Weather {
+    pub kind: WeatherKind,
+    // Seconds since this spell of weather started, and how long it lasts in total.
+    pub elapsed: f32,
+    pub duration: f32,
+    // The direction the wind blows towards, in radians.
+    pub wind_angle: f32,
+}
+
+// Worlds saved before weather existed start with a clear sky.
+impl Default for Weather {
+    fn default() -> Weather {
+        Weather { kind: WeatherKind::Clear, elapsed: 0.0, duration: 60.0, wind_angle: 0.0 }
+    }
+}
+
+impl Weather {
+    // How strong the weather is right now, from 0 to 1. It ramps up at the start of the spell
+    // and back down at its end, so changes in the weather are never sudden.
+    pub fn intensity(&self) -> f32 {
+        if self.kind == WeatherKind::Clear {
+            return 0.0;
+        }
+        let remaining = (self.duration - self.elapsed).max(0.0);
+        (self.elapsed.min(remaining) / FADE_TIME).min(1.0)
+    }
+
+    // Advance the weather by dt seconds. When the current spell is over, the next one is picked
+    // with the configured weights, never the same kind twice in a row unless it is the only kind
+    // with a weight. Returns true if a different kind of weather has just arrived.
+    pub fn update(&mut self, dt: f32, config: &WeatherConfig, rng: &mut Rng) -> bool {
+        self.elapsed += dt;
+        if self.elapsed < self.duration {
+            return false;
+        }
+        let previous = self.kind;
+        let weights = &config.weights;
+        let others = weights.total() - weights.get(previous);
+        if others > 0 {
+            let mut roll = rng.range_u32(0, others);
+            for kind in WeatherKind::ALL.into_iter().filter(|kind| *kind != previous) {
+                let weight = weights.get(kind);
+                if roll < weight {
+                    self.kind = kind;
+                    break;
+                }
+                roll -= weight;
+            }
+        }
+        let spread = (config.max_duration - config.min_duration) as u32;
+        self.duration = config.min_duration + rng.range_u32(0, spread + 1) as f32;
+        self.elapsed = 0.0;
+        if self.kind == WeatherKind::Wind {
+            self.wind_angle = (rng.range_u32(0, 360) as f32).to_radians();
+        }
+        self.kind != previous
+    }
+
+    // The effects of the weather on the simulation, blended in by its intensity.
+    pub fn effects(&self, config: &Config) -> WeatherEffects {
+        let weather = &config.weather;
+        let intensity = self.intensity();
+        let mut effects = WeatherEffects { friction: config.movement.friction, happiness_loss: 0.0, drain: 1.0, wind: Vec2::ZERO };
+        match self.kind {
+            WeatherKind::Clear => {}
+            WeatherKind::Rain => {
+                effects.friction += (weather.rain_friction - effects.friction) * intensity;
+                effects.happiness_loss = weather.rain_happiness * intensity;
+            }
+            WeatherKind::Wind => {
+                // The wind comes in gusts.
+                let gust = 0.75 + 0.25 * (self.elapsed * 1.3).sin();
+                effects.wind = Vec2::from_angle(self.wind_angle) * weather.wind_strength * intensity * gust;
+            }
+            WeatherKind::Heat => effects.drain += (weather.heat_drain - 1.0) * intensity,
+        }
+        effects
+    }
+}
@@ -0,0 +1,834 @@
+use std::f32::consts::{FRAC_PI_3, PI, TAU};
+
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::ball::{BALL_RADIUS, Ball};
+use crate::bug::{self, Bug, BugKind, SquashedBug};
+use crate::config::{Config, Difficulty, ItemConfig};
+use crate::daycycle;
+use crate::fetch::{Fetch, FetchState};
+use crate::forage::Forage;
+use crate::growth::{Action, LifeStage};
+use crate::input::InputState;
+use crate::inventory::{GroundItem, ItemCategory, ItemKind};
+use crate::pet::Pet;
+use crate::rng::Rng;
+use crate::spatial::SpatialGrid;
+use crate::chunks::ChunkManager;
+use crate::terrain::{self, FeatureKind};
+use crate::weather::Weather;
+
+// The length of one fixed simulation tick in seconds.
+// The game loop always advances the world in steps of this size, so the gameplay is identical
+// whether the monitor runs at 60Hz or 144Hz.
+pub const TICK: f32 = 1.0 / 60.0;
+
+// How many ticks pass between two updates of a swarmer's flocking.
+const FLOCK_INTERVAL: usize = 4;
+
+// The World struct owns everything that takes part in the simulation: the pet, the ball,
+// the bugs and all gameplay timers. It never touches macroquad's input or rendering,
+// so it can be stepped headlessly with any InputState.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct World {
+    // The pet the player is taking care of.
+    pub pet: Pet,
+    // The ball the pet can kick around, carry and throw.
+    pub ball: Ball,
+    // The game of fetch with the ball, if one is going on.
+    #[serde(default)]
+    pub fetch: Fetch,
+    // Bugs that chase the pet.
+    pub bugs: Vec<Bug>,
+    // The bugs sorted by position, rebuilt every tick. It is not saved; the next tick rebuilds it.
+    #[serde(skip)]
+    pub bug_grid: SpatialGrid,
+    // The chunks around the pet. They are not saved; chunks are simply generated again from the seed.
+    #[serde(skip)]
+    pub chunks: ChunkManager,
+    // The berries picked and puddles drunk from that have not grown back yet.
+    #[serde(default)]
+    pub forage: Forage,
+    // Items lying on the ground, waiting for the pet to pick them up.
+    #[serde(default)]
+    pub items: Vec<GroundItem>,
+    // Seconds accumulated towards the next item appearing.
+    #[serde(default)]
+    pub item_spawn_timer: f32,
+    // The weather rolling through the world.
+    #[serde(default)]
+    pub weather: Weather,
+    // Bugs squashed by the pet or the ball, lying on the ground until eaten or rotted away.
+    #[serde(default)]
+    pub squashed: Vec<SquashedBug>,
+    // How many bugs the pet has squashed this run.
+    #[serde(default)]
+    pub kills: u32,
+    // Speed shared by every bug, which slowly increases over time.
+    pub current_bug_speed: f32,
+    // Seconds accumulated towards the next wave of bugs.
+    pub bug_spawn_timer: f32,
+    // Seconds accumulated towards the next bug speed increase.
+    pub speed_increase_timer: f32,
+    // Total simulated time in seconds since this world was created.
+    pub time: f64,
+    // The seed this run was started with. It drives bug spawning and the terrain features.
+    pub seed: u64,
+    // The random number generator for gameplay, created from the seed.
+    pub rng: Rng,
+    // The difficulty chosen on the start screen.
+    pub difficulty: Difficulty,
+    // The gameplay tuning values this world runs with. They are not saved: a loaded world runs with
+    // the configuration file as it is now, and a replay keeps its own copy.
+    #[serde(skip)]
+    pub config: Config,
+    // The latest system message displayed in the HUD.
+    pub message: String,
+}
+
+// How fast the pet turns while fetching, in radians per 60Hz frame.
+const FETCH_TURN_SPEED: f32 = 0.12;
+// Speed a kicked ball gets for every unit between the pet and the ball, per 60Hz frame.
+const KICK_SPEED: f32 = 0.2;
+// Seconds after a throw before the pet can catch the ball.
+const FETCH_CATCH_DELAY: f32 = 0.5;
+
+impl World {
+    // Create a fresh world around a newly named pet of the given species.
+    // The same name, species and seed always produce the same starting world.
+    pub fn new(name: String, species: &str, seed: u64, difficulty: Difficulty, config: Config) -> World {
+        let mut pet = Pet::new(name, config.species.get(species));
+        // The pet starts out with the items the configuration gives every new pet.
+        for kind in ItemKind::ALL {
+            pet.inventory.add(kind, config.items.get(kind).start, config.items.max_stack);
+        }
+        let preset = config.difficulty.get(difficulty);
+        let speed = preset.start_speed;
+        // Initialize the first bugs at a safe distance from the pet's starting location.
+        // Difficulties without bugs (or with a tiny cap) start with fewer of them.
+        let mut bugs = vec![
+            Bug::new(pet.x + 30.0, pet.z + 30.0, speed, BugKind::Swarmer, 1.0),
+            Bug::new(pet.x - 30.0, pet.z + 45.0, speed, BugKind::Swarmer, 1.0),
+            Bug::new(pet.x + 45.0, pet.z - 30.0, speed, BugKind::Swarmer, 1.0),
+        ];
+        if preset.spawn_count == 0 {
+            bugs.clear();
+        }
+        bugs.truncate(preset.max_bugs as usize);
+        let mut world = World {
+            pet,
+            ball: Ball::new(),
+            fetch: Fetch::default(),
+            bugs,
+            bug_grid: SpatialGrid::default(),
+            chunks: ChunkManager::default(),
+            forage: Forage::default(),
+            items: Vec::new(),
+            item_spawn_timer: 0.0,
+            weather: Weather::default(),
+            squashed: Vec::new(),
+            kills: 0,
+            current_bug_speed: speed,
+            bug_spawn_timer: 0.0,
+            speed_increase_timer: 0.0,
+            time: 0.0,
+            seed,
+            rng: Rng::new(seed),
+            difficulty,
+            config,
+            message: "Take care of your 3D pet!".to_string(),
+        };
+        world.prepare_chunks();
+        // Stand the pet and the ball on the ground from the start.
+        world.pet.y = world.ground_height(world.pet.x, world.pet.z);
+        world.ball.y = world.ground_height(world.ball.x, world.ball.z);
+        world
+    }
+
+    // Generate the chunks coming into view around the pet and forget the ones far behind it.
+    // A loaded world starts without chunks, and a new chunk size in the config changes the whole
+    // layout, so in those cases the chunks start over from the seed.
+    pub fn prepare_chunks(&mut self) {
+        let chunk_size = self.config.terrain.chunk_size;
+        if !self.chunks.matches(self.seed, chunk_size) {
+            self.chunks = ChunkManager::new(self.seed, chunk_size);
+        }
+        // Chunks are kept as long as bugs may still walk through them,
+        // so they are not generated over and over again for the bugs at the edge.
+        let view_dist = self.config.terrain.view_dist as i32;
+        let keep_dist = view_dist.max((self.config.bugs.despawn_distance / chunk_size).ceil() as i32) + 1;
+        self.chunks.update(self.pet.x, self.pet.z, view_dist, keep_dist);
+    }
+
+    // The height things stand on at a position: the ground, or the water surface in ponds.
+    pub fn ground_height(&self, x: f32, z: f32) -> f32 {
+        terrain::ground_height(self.seed, x, z)
+    }
+
+    // The hour of the day on the in-game clock, from 0 up to 24.
+    pub fn hour(&self) -> f32 {
+        daycycle::hour(self.time, &self.config.day)
+    }
+
+    // How light it is, from 0 at night to 1 in the day.
+    pub fn daylight(&self) -> f32 {
+        daycycle::daylight(self.hour())
+    }
+
+    // Advance the whole simulation by dt seconds using the given input.
+    pub fn step(&mut self, dt: f32, input: &InputState) {
+        let was_light = self.daylight() >= 0.5;
+        self.time += dt as f64;
+        let now = self.time;
+        // Per-frame amounts below were tuned for 60 frames per second.
+        let frames = dt * 60.0;
+
+        // Announce nightfall and sunrise. In the dark, bugs come more often and move faster.
+        let night = 1.0 - self.daylight();
+        if was_light != (night <= 0.5) && self.pet.is_alive {
+            self.message = if was_light { "Night falls. The bugs are getting bolder...".to_string() } else { "The sun is rising.".to_string() };
+        }
+        let day = &self.config.day;
+        let night_speed = 1.0 + (day.night_speed_factor - 1.0) * night;
+        let night_spawn = 1.0 + (day.night_spawn_factor - 1.0) * night;
+
+        // Roll the weather on, and announce when it changes.
+        if self.weather.update(dt, &self.config.weather, &mut self.rng) && self.pet.is_alive {
+            self.message = self.weather.kind.announcement().to_string();
+        }
+        let weather = self.weather.effects(&self.config);
+
+        self.prepare_chunks();
+        let mut obstacles = Vec::new();
+
+        // An egg cannot move, so its movement keys are dropped.
+        let held_still;
+        let input = if input.is_moving() && self.pet.is_alive && !self.allowed(Action::Move) {
+            held_still = InputState { forward: false, back: false, left: false, right: false, stick: Vec2::ZERO, ..input.clone() };
+            &held_still
+        } else {
+            input
+        };
+
+        // While fetching, the pet runs after the ball or back with it by itself. Taking over with the
+        // movement keys calls the fetch off.
+        let fetching;
+        let input = if self.fetch.active() && self.pet.is_alive {
+            if input.is_moving() {
+                self.fetch.give_up();
+                self.message = format!("{} stopped fetching.", self.pet.name);
+                input
+            } else {
+                fetching = self.steer_fetch(input, frames);
+                &fetching
+            }
+        } else {
+            input
+        };
+
+        // Update the pet's logic (movement, stats, etc.), then keep it out of trees and cacti.
+        let (was_asleep, stage) = (self.pet.asleep, self.pet.stage);
+        self.pet.update(dt, input, now, &self.config, &weather);
+        if was_asleep && !self.pet.asleep && self.pet.is_alive {
+            self.message = format!("{} woke up.", self.pet.name);
+        }
+        if self.pet.stage > stage && self.pet.is_alive {
+            self.message = self.stage_message();
+        }
+        // A jumping pet clears the obstacles below its feet.
+        self.chunks.colliders_near(self.pet.x, self.pet.z, self.pet.radius(), &mut obstacles);
+        let feet = self.pet.y + self.pet.height;
+        obstacles.retain(|obstacle| obstacle.top() > feet);
+        self.pet.collide(&obstacles);
+        self.pet.y = self.ground_height(self.pet.x, self.pet.z);
+        // Update the ball's logic (physics, rotation), bouncing it off trees and cacti.
+        // A ball the pet is carrying just stays in front of it.
+        if self.ball.held {
+            let reach = self.pet.radius() + BALL_RADIUS * 0.5;
+            self.ball.x = self.pet.x + self.pet.yaw.sin() * reach;
+            self.ball.z = self.pet.z + self.pet.yaw.cos() * reach;
+            self.ball.height = self.pet.height + self.pet.radius() - BALL_RADIUS * 0.5;
+            self.ball.vx = 0.0;
+            self.ball.vz = 0.0;
+            self.ball.vy = 0.0;
+            self.ball.spin = [0.0; 3];
+        } else {
+            // How steeply the ground rises under the ball, along x and along z.
+            let (x, z) = (self.ball.x, self.ball.z);
+            let slope = Vec2::new(
+                self.ground_height(x + 0.5, z) - self.ground_height(x - 0.5, z),
+                self.ground_height(x, z + 0.5) - self.ground_height(x, z - 0.5),
+            );
+            self.ball.update(dt, weather.friction, weather.wind, slope);
+            self.chunks.colliders_near(self.ball.x, self.ball.z, BALL_RADIUS, &mut obstacles);
+            self.ball.bounce(&obstacles);
+        }
+        self.ball.y = self.ground_height(self.ball.x, self.ball.z);
+
+        let bugs_config = &self.config.bugs;
+        let preset = self.config.difficulty.get(self.difficulty).clone();
+
+        // Move every bug towards the pet at the current global speed, around the obstacles ahead of it.
+        for bug in self.bugs.iter_mut() {
+            bug.speed = self.current_bug_speed * night_speed;
+            self.chunks.colliders_near(bug.x, bug.z, bug::LOOKAHEAD, &mut obstacles);
+            bug.update(dt, self.pet.x, self.pet.z, &obstacles);
+        }
+
+        // Bugs that wandered too far from the pet are removed so the bug list cannot grow forever.
+        let (pet_x, pet_z) = (self.pet.x, self.pet.z);
+        let despawn_sq = bugs_config.despawn_distance * bugs_config.despawn_distance;
+        self.bugs.retain(|bug| {
+            let (dx, dz) = (bug.x - pet_x, bug.z - pet_z);
+            dx * dx + dz * dz <= despawn_sq
+        });
+
+        // Spawn a wave of new bugs at the rate of the chosen difficulty,
+        // as long as there are fewer bugs than the difficulty allows.
+        self.bug_spawn_timer += dt * night_spawn;
+        while self.bug_spawn_timer >= preset.spawn_interval {
+            let room = (preset.max_bugs as usize).saturating_sub(self.bugs.len());
+            for _ in 0..(preset.spawn_count as usize).min(room) {
+                // Spawn bugs at random angles around the pet.
+                let rand_angle = (self.rng.range_u32(0, 360) as f32).to_radians();
+                // Ensure bugs spawn far enough away to avoid "instant spawning on you".
+                // Distance increases as the bugs get faster, providing a reaction window.
+                let dist = preset.spawn_distance + (self.current_bug_speed * 2.0).min(500.0);
+                // The difficulty's weights decide which kind of bug it is.
+                let kind = preset.weights.pick(self.rng.range_u32(0, preset.weights.total()));
+                let orbit_dir = if self.rng.range_u32(0, 2) == 0 { 1.0 } else { -1.0 };
+                // Bugs never appear inside a tree trunk or a cactus.
+                let mut pos = Vec2::new(self.pet.x + rand_angle.cos() * dist, self.pet.z + rand_angle.sin() * dist);
+                self.chunks.colliders_near(pos.x, pos.y, kind.radius(), &mut obstacles);
+                terrain::push_out(&mut pos, kind.radius(), &obstacles);
+                self.bugs.push(Bug::new(pos.x, pos.y, self.current_bug_speed, kind, orbit_dir));
+            }
+            self.bug_spawn_timer -= preset.spawn_interval;
+        }
+
+        // Sort the bugs into the spatial grid, so only bugs near the pet are checked below
+        // and the renderer only looks at bugs that are in view.
+        self.bug_grid.rebuild(self.bugs.iter().map(|bug| (bug.x, bug.z)));
+
+        // Swarmers steer with their flock, which they pick up here and follow on the next ticks.
+        // Looking at the neighbours is the expensive part with thousands of bugs, so each swarmer
+        // only does it every FLOCK_INTERVAL ticks, spread out over the ticks by index.
+        let tick = (self.time / TICK as f64).round() as usize;
+        for i in 0..self.bugs.len() {
+            if self.bugs[i].kind == BugKind::Swarmer && (i + tick).is_multiple_of(FLOCK_INTERVAL) {
+                self.bugs[i].flock = bug::flock_steering(&self.bugs, &self.bug_grid, i).into();
+            }
+        }
+
+        // Check for collisions between the nearby bugs and the pet.
+        for i in self.bug_grid.query(pet_x, pet_z, bugs_config.threat_radius) {
+            let bug = &self.bugs[i];
+            let bdx = self.pet.x - bug.x;
+            let bdz = self.pet.z - bug.z;
+            let bdist = (bdx * bdx + bdz * bdz).sqrt();
+            // A flyer in the middle of a hop passes over the pet's head, and a jumping pet over the bugs.
+            if bdist < bugs_config.contact_radius && (bug.y - self.pet.height).abs() < 1.0 && self.pet.is_alive {
+                // If a bug touches the pet, it bites: the pet loses health and is knocked back.
+                let health = &self.config.health;
+                let damage = health.bug_damage * self.config.species.get(&self.pet.species).ability.bite_damage();
+                if self.pet.take_damage(damage, bug.x, bug.z, now, health) {
+                    self.message = if self.pet.is_alive {
+                        format!("{} was bitten by a bug!", self.pet.name)
+                    } else {
+                        format!("{} was killed by a bug!", self.pet.name)
+                    };
+                }
+            } else if bdist < bugs_config.threat_radius && self.pet.is_alive {
+                // If a bug is just very close, it still drains stats and warns the player.
+                self.pet.happiness -= 0.1 * frames;
+                self.pet.energy -= 0.05 * frames;
+                // Keep the bite message on screen while the pet recovers from it.
+                if self.pet.invulnerable <= 0.0 {
+                    self.message = "A bug is closing in!".to_string();
+                }
+            }
+        }
+
+        // The ball bounces off the bugs it runs into, knocking them back, unless one of them is
+        // passing over the other. Rolling fast enough it squashes them. The bug's radius depends on its kind.
+        let combat = &self.config.combat;
+        let ball_speed = self.ball.speed();
+        let touching: Vec<usize> = if self.ball.held {
+            Vec::new()
+        } else {
+            self.bug_grid
+                .query(self.ball.x, self.ball.z, BALL_RADIUS + BugKind::Beetle.radius())
+                .into_iter()
+                .filter(|&i| {
+                    let bug = &self.bugs[i];
+                    let hit_radius = BALL_RADIUS + bug.kind.radius();
+                    let (dx, dz) = (bug.x - self.ball.x, bug.z - self.ball.z);
+                    dx * dx + dz * dz < hit_radius * hit_radius && (bug.y - self.ball.height).abs() < 1.0
+                })
+                .collect()
+        };
+        for &i in &touching {
+            let bug = &mut self.bugs[i];
+            let away = Vec2::new(bug.x - self.ball.x, bug.z - self.ball.z).normalize_or(Vec2::X);
+            let knockback = away * ball_speed * combat.ball_knockback;
+            bug.knockback = (knockback.x, knockback.y);
+            self.ball.deflect(-away);
+        }
+        if ball_speed > combat.ball_kill_speed && !touching.is_empty() {
+            let count = self.hit_bugs(touching);
+            if count > 0 {
+                self.message = format!("The ball squashed {} bug(s)!", count);
+            }
+        }
+
+        // Squashed bugs rot away after a while.
+        let lifetime = self.config.combat.squashed_lifetime;
+        for squashed in self.squashed.iter_mut() {
+            squashed.age += dt;
+        }
+        self.squashed.retain(|squashed| squashed.age < lifetime);
+
+        // Increase bug speed by a very small amount every few seconds.
+        // Difficulties without bugs or without a speed ramp never show the message.
+        self.speed_increase_timer += dt;
+        while self.speed_increase_timer >= preset.speed_increase_interval {
+            self.speed_increase_timer -= preset.speed_increase_interval;
+            if preset.speed_increase > 0.0 && preset.max_bugs > 0 {
+                self.current_bug_speed += preset.speed_increase; // Very small speed increase.
+                self.message = "Speed slightly increased...".to_string();
+            }
+        }
+
+        if self.pet.is_alive {
+            self.update_fetch(dt, input);
+        }
+        self.update_items(dt);
+
+        let acting = input.feed || input.play || input.use_item || input.pounce || input.eat || input.gather || input.jump || input.dash;
+        if self.pet.is_alive && self.pet.asleep {
+            // A sleeping pet does nothing until it wakes up. Pressing sleep again wakes it.
+            if input.sleep {
+                self.pet.asleep = false;
+                self.message = format!("You woke {} up.", self.pet.name);
+            } else if acting {
+                self.message = format!("{} is fast asleep. Press 3 to wake it up.", self.pet.name);
+            }
+        } else if self.pet.is_alive {
+            // Handle manual interaction actions (feed, play, sleep) while the pet is alive.
+            // Feeding and playing use the selected item if it fits, otherwise the first one carried.
+            if input.next_item {
+                self.pet.inventory.select_next();
+            }
+            if input.feed {
+                match self.pet.inventory.pick(ItemCategory::Food) {
+                    Some(kind) => self.use_item(kind),
+                    None => self.message = "No food! Pick berries from red flowers (G).".to_string(),
+                }
+            }
+            // Without a toy of its own, the pet can play with the ball if it is close by.
+            if input.play {
+                match self.pet.inventory.pick(ItemCategory::Toy) {
+                    Some(kind) => self.use_item(kind),
+                    None if self.ball_distance() < self.config.fetch.play_radius => self.play_with_ball(),
+                    None => self.message = format!("{} has no toy nearby. Carry a toy or bring the ball close.", self.pet.name),
+                }
+            }
+            if input.use_item {
+                match self.pet.inventory.selected() {
+                    Some(kind) => self.use_item(kind),
+                    None => self.message = "The inventory is empty.".to_string(),
+                }
+            }
+            if input.sleep && self.allowed(Action::Sleep) {
+                self.message = if self.pet.sleep() {
+                    format!("{} fell asleep...", self.pet.name)
+                } else {
+                    "Stand still to sleep!".to_string()
+                };
+            }
+            if input.jump && self.allowed(Action::Jump) {
+                self.jump();
+            }
+            if input.dash && self.allowed(Action::Dash) {
+                self.dash();
+            }
+            if input.pounce && self.allowed(Action::Pounce) {
+                self.pounce();
+            }
+            if input.eat && self.allowed(Action::EatBug) {
+                self.eat_squashed_bug();
+            }
+            if input.gather && self.allowed(Action::Forage) {
+                self.gather(now);
+            }
+        }
+        self.forage.regrow(now);
+
+        // The pet's mood follows its stats and the closest bug.
+        if self.pet.is_alive {
+            let calm_radius = self.config.mood.calm_radius;
+            let (pet_x, pet_z) = (self.pet.x, self.pet.z);
+            let nearest_bug = self
+                .bug_grid
+                .nearby(pet_x, pet_z, calm_radius)
+                .map(|i| {
+                    let (dx, dz) = (self.bugs[i].x - pet_x, self.bugs[i].z - pet_z);
+                    (dx * dx + dz * dz).sqrt()
+                })
+                .reduce(f32::min);
+            let mood = self.pet.mood.next(&self.pet, nearest_bug, &self.config.mood);
+            if mood != self.pet.mood {
+                self.pet.mood = mood;
+                self.message = mood.message(&self.pet.name);
+            }
+        }
+    }
+
+    // Pick up the items the pet walks over, let new ones appear around it from time to time,
+    // and forget the ones it left far behind.
+    fn update_items(&mut self, dt: f32) {
+        let items = &self.config.items;
+        let (pet_x, pet_z) = (self.pet.x, self.pet.z);
+        if self.pet.is_alive {
+            let reach_sq = items.pickup_radius * items.pickup_radius;
+            let mut index = 0;
+            while index < self.items.len() {
+                let item = &self.items[index];
+                let (dx, dz) = (item.x - pet_x, item.z - pet_z);
+                // Items that do not fit in the inventory stay where they are.
+                if dx * dx + dz * dz < reach_sq && self.pet.inventory.add(item.kind, 1, items.max_stack) > 0 {
+                    self.message = format!("{} picked up the {}.", self.pet.name, item.kind.name());
+                    self.items.remove(index);
+                } else {
+                    index += 1;
+                }
+            }
+        }
+
+        let despawn_sq = self.config.bugs.despawn_distance * self.config.bugs.despawn_distance;
+        self.items.retain(|item| {
+            let (dx, dz) = (item.x - pet_x, item.z - pet_z);
+            dx * dx + dz * dz <= despawn_sq
+        });
+
+        self.item_spawn_timer += dt;
+        while self.item_spawn_timer >= items.spawn_interval {
+            self.item_spawn_timer -= items.spawn_interval;
+            let total = items.total_weight();
+            if total == 0 || self.items.len() >= items.max_ground as usize {
+                continue;
+            }
+            let kind = items.pick(self.rng.range_u32(0, total));
+            let angle = (self.rng.range_u32(0, 360) as f32).to_radians();
+            let spread = (items.spawn_max_distance - items.spawn_min_distance) as u32;
+            let dist = items.spawn_min_distance + self.rng.range_u32(0, spread + 1) as f32;
+            self.items.push(GroundItem { kind, x: pet_x + angle.cos() * dist, z: pet_z + angle.sin() * dist });
+        }
+    }
+
+    // Use an item from the inventory the way its category says: eat it, play with it or take it.
+    fn use_item(&mut self, kind: ItemKind) {
+        let action = if kind.category() == ItemCategory::Toy { Action::Play } else { Action::Feed };
+        if !self.allowed(action) {
+            return;
+        }
+        let item = self.config.items.get(kind);
+        let name = kind.name();
+        self.message = match kind.category() {
+            ItemCategory::Food if self.pet.feed(kind, item) => format!("You fed {} the {}!", self.pet.name, name),
+            ItemCategory::Food => "Stand still to eat!".to_string(),
+            ItemCategory::Toy if self.pet.play(item) => format!("You played with {} and the {}!", self.pet.name, name),
+            ItemCategory::Toy => "Stand still to play!".to_string(),
+            ItemCategory::Medicine if self.pet.take_medicine(kind, item) => format!("{} took the {}.", self.pet.name, name),
+            ItemCategory::Medicine => format!("{} cannot take the {} now.", self.pet.name, name),
+        };
+    }
+
+    // The distance between the pet and the ball.
+    fn ball_distance(&self) -> f32 {
+        let (dx, dz) = (self.ball.x - self.pet.x, self.ball.z - self.pet.z);
+        (dx * dx + dz * dz).sqrt()
+    }
+
+    // Play with the ball for a moment, the way the pet plays with a toy.
+    fn play_with_ball(&mut self) {
+        if !self.allowed(Action::Play) {
+            return;
+        }
+        let fetch = &self.config.fetch;
+        let ball = ItemConfig { happiness: fetch.play_happiness, energy: -fetch.play_energy, ..ItemConfig::default() };
+        self.message = if self.pet.play(&ball) {
+            format!("You played ball with {}!", self.pet.name)
+        } else {
+            "Stand still to play!".to_string()
+        };
+    }
+
+    // The input that makes the pet run after the ball, or back to the throwing spot with it.
+    // The pet turns towards where it is going and runs once it roughly faces it.
+    fn steer_fetch(&mut self, input: &InputState, frames: f32) -> InputState {
+        let (target_x, target_z) = match self.fetch.state {
+            FetchState::Returning { .. } => (self.fetch.origin_x, self.fetch.origin_z),
+            _ => (self.ball.x, self.ball.z),
+        };
+        let heading = (target_x - self.pet.x).atan2(target_z - self.pet.z);
+        let turn = (heading - self.pet.yaw + PI).rem_euclid(TAU) - PI;
+        let max_turn = FETCH_TURN_SPEED * frames;
+        self.pet.yaw += turn.clamp(-max_turn, max_turn);
+        InputState { forward: turn.abs() < FRAC_PI_3, ..input.clone() }
+    }
+
+    // Pick up the ball, wind up and throw it, and follow the pet as it fetches it.
+    // The pet only picks the ball up when told to; otherwise walking into it kicks it.
+    fn update_fetch(&mut self, dt: f32, input: &InputState) {
+        let fetch = &self.config.fetch;
+        let name = &self.pet.name;
+        let can_play = self.pet.stage.allows(Action::Play) && !self.pet.asleep;
+        // The pet can only grab the ball when it is not flying over its head, or under it while it jumps.
+        let near = self.ball_distance() < fetch.reach && (self.ball.height - self.pet.height).abs() < self.pet.radius() * 2.0;
+
+        // The throw key that picked the ball up has to be let go before it winds up a throw.
+        if !input.throw {
+            self.fetch.grabbed = false;
+        }
+        // Holding the throw key winds up the throw, and letting it go throws the ball the way
+        // the pet is facing, harder the longer the key was held.
+        if input.throw && self.ball.held && can_play && !self.fetch.active() && !self.fetch.grabbed {
+            self.fetch.charge += dt;
+        } else if self.fetch.charge > 0.0 && self.ball.held && can_play {
+            let speed = Fetch::throw_speed(self.fetch.power(fetch), fetch);
+            self.ball.held = false;
+            self.ball.vx = self.pet.yaw.sin() * speed;
+            self.ball.vz = self.pet.yaw.cos() * speed;
+            self.ball.vy = speed * fetch.loft;
+            // The ball rolls off the pet's mouth, so it flies with topspin.
+            self.ball.spin = self.ball.rolling_spin();
+            self.fetch.throw(self.pet.x, self.pet.z);
+            self.message = format!("You threw the ball! Fetch, {}!", name);
+        } else if self.fetch.charge > 0.0 {
+            // The pet fell asleep while the throw was wound up.
+            self.fetch.charge = 0.0;
+        } else if input.throw && !self.ball.held && !self.fetch.active() && !self.fetch.grabbed {
+            // The throw key next to the ball picks it up.
+            if near && can_play {
+                self.ball.held = true;
+                self.fetch.grabbed = true;
+                self.message = format!("{} picked up the ball. Hold T to throw it!", name);
+            } else {
+                self.message = format!("{} needs to be next to the ball to pick it up.", name);
+            }
+        }
+
+        self.fetch.elapsed += dt;
+        match self.fetch.state {
+            // The ball has to get away from the pet before it can be caught.
+            FetchState::Chasing if near && self.fetch.elapsed > FETCH_CATCH_DELAY => {
+                let (dx, dz) = (self.ball.x - self.fetch.origin_x, self.ball.z - self.fetch.origin_z);
+                self.ball.held = true;
+                self.fetch.state = FetchState::Returning { distance: (dx * dx + dz * dz).sqrt() };
+                self.message = format!("{} caught the ball!", name);
+            }
+            FetchState::Returning { distance } => {
+                let (dx, dz) = (self.pet.x - self.fetch.origin_x, self.pet.z - self.fetch.origin_z);
+                if dx * dx + dz * dz < fetch.return_radius * fetch.return_radius {
+                    let happiness = self.fetch.complete(distance, fetch);
+                    self.pet.happiness += happiness;
+                    self.message = format!(
+                        "{} brought the ball back from {:.0} away! +{:.0} happiness, {} in a row",
+                        name, distance, happiness, self.fetch.streak
+                    );
+                }
+            }
+            // Running into the ball without picking it up kicks it away from the pet.
+            FetchState::Idle if near && !self.ball.held => {
+                self.ball.vx = (self.ball.x - self.pet.x) * KICK_SPEED;
+                self.ball.vz = (self.ball.z - self.pet.z) * KICK_SPEED;
+            }
+            FetchState::Idle | FetchState::Chasing => {}
+        }
+        if self.fetch.active() && self.fetch.elapsed > fetch.timeout {
+            self.fetch.give_up();
+            self.message = format!("{} lost interest in the ball.", name);
+        }
+    }
+
+    // Whether the pet is old enough, or still young enough, to do an action.
+    // If it is not, the message says why.
+    fn allowed(&mut self, action: Action) -> bool {
+        let stage = self.pet.stage;
+        if stage.allows(action) {
+            return true;
+        }
+        let name = &self.pet.name;
+        self.message = match stage {
+            LifeStage::Egg => format!("{} is still an egg. Wait for it to hatch!", name),
+            LifeStage::Elder => format!("{} is too old to {}.", name, action.verb()),
+            _ => format!("{} is too young to {}.", name, action.verb()),
+        };
+        false
+    }
+
+    // The message shown when the pet reaches a new life stage.
+    fn stage_message(&self) -> String {
+        let name = &self.pet.name;
+        match (self.pet.stage, self.pet.form) {
+            (LifeStage::Egg | LifeStage::Baby, _) => format!("{} hatched!", name),
+            (LifeStage::Child, _) => format!("{} is growing up!", name),
+            (LifeStage::Adult, Some(form)) => format!("{} grew up into a {} adult!", name, form.name()),
+            (LifeStage::Adult, None) => format!("{} grew up!", name),
+            (LifeStage::Elder, _) => format!("{} is getting old.", name),
+        }
+    }
+
+    // Pick a berry from the closest ripe flower within reach, or drink from the closest full puddle.
+    fn gather(&mut self, now: f64) {
+        let forage = &self.config.forage;
+        let (pet_x, pet_z) = (self.pet.x, self.pet.z);
+        let (pet_cx, pet_cz) = self.chunks.chunk_of(pet_x, pet_z);
+        // Features near the edge of the pet's chunk may belong to a neighbouring one.
+        let mut closest = None;
+        let reach = forage.reach * self.config.species.get(&self.pet.species).ability.forage_reach();
+        let mut closest_sq = reach * reach;
+        for cx in pet_cx - 1..=pet_cx + 1 {
+            for cz in pet_cz - 1..=pet_cz + 1 {
+                for (index, feature) in self.chunks.chunk(cx, cz).features.iter().enumerate() {
+                    if !matches!(feature.kind, FeatureKind::Flower | FeatureKind::Puddle) {
+                        continue;
+                    }
+                    let (dx, dz) = (feature.x - pet_x, feature.z - pet_z);
+                    let dist_sq = dx * dx + dz * dz;
+                    if dist_sq < closest_sq && self.forage.is_ripe(cx, cz, index, now) {
+                        closest = Some((cx, cz, index, feature.kind));
+                        closest_sq = dist_sq;
+                    }
+                }
+            }
+        }
+        match closest {
+            Some((cx, cz, index, FeatureKind::Puddle)) => {
+                self.forage.take(cx, cz, index, now + forage.puddle_regrow as f64);
+                self.pet.energy = (self.pet.energy + forage.drink_energy).min(100.0);
+                self.message = format!("{} drank from the puddle.", self.pet.name);
+            }
+            Some((cx, cz, index, _)) => {
+                if self.pet.inventory.add(ItemKind::Berry, 1, self.config.items.max_stack) == 0 {
+                    self.message = format!("{} cannot carry any more berries.", self.pet.name);
+                    return;
+                }
+                self.forage.take(cx, cz, index, now + forage.berry_regrow as f64);
+                self.message = format!("{} picked a berry.", self.pet.name);
+            }
+            None => self.message = "Nothing to forage nearby.".to_string(),
+        }
+    }
+
+    // Hit the bugs at the given indices once. Bugs without health left are removed, leaving
+    // squashed bugs behind and counting as kills; armoured ones may survive a hit.
+    // Returns how many bugs were squashed.
+    fn hit_bugs(&mut self, mut indices: Vec<usize>) -> usize {
+        indices.sort_unstable();
+        indices.dedup();
+        let mut squashed = vec![false; self.bugs.len()];
+        let mut count = 0;
+        for &i in &indices {
+            let bug = &mut self.bugs[i];
+            if bug.hit() {
+                squashed[i] = true;
+                count += 1;
+                self.squashed.push(SquashedBug { x: bug.x, z: bug.z, age: 0.0 });
+            }
+        }
+        if count == 0 {
+            return 0;
+        }
+        let mut index = 0;
+        self.bugs.retain(|_| {
+            let keep = !squashed[index];
+            index += 1;
+            keep
+        });
+        // The grid points at the old indices, so sort the remaining bugs in again.
+        self.bug_grid.rebuild(self.bugs.iter().map(|bug| (bug.x, bug.z)));
+        self.kills += count as u32;
+        count
+    }
+
+    // Jump, or jump again in mid-air. The message says why the pet did not jump.
+    fn jump(&mut self) {
+        let jump = &self.config.jump;
+        if self.pet.jump(jump) {
+            return;
+        }
+        if self.pet.energy < jump.energy_cost {
+            self.message = format!("{} is too tired to jump!", self.pet.name);
+        } else if self.pet.jumps == 1 {
+            self.message = format!("{} needs more energy for a double jump.", self.pet.name);
+        }
+    }
+
+    // Dash away. The message says why the pet did not dash.
+    fn dash(&mut self) {
+        let sprint = &self.config.sprint;
+        if self.pet.dash(sprint) {
+            return;
+        }
+        self.message = if self.pet.dash_cooldown > 0.0 {
+            format!("{} needs {:.1}s to catch its breath before dashing again.", self.pet.name, self.pet.dash_cooldown)
+        } else {
+            format!("{} is too tired to dash!", self.pet.name)
+        };
+    }
+
+    // Pounce: squash every bug around the pet, at the cost of some energy.
+    fn pounce(&mut self) {
+        let combat = &self.config.combat;
+        let ability = self.config.species.get(&self.pet.species).ability;
+        let cost = combat.pounce_energy_cost * ability.pounce_cost();
+        if self.pet.energy < cost {
+            self.message = format!("{} is too tired to pounce!", self.pet.name);
+            return;
+        }
+        self.pet.energy -= cost;
+        let radius = combat.pounce_radius * ability.pounce_reach();
+        let (pet_x, pet_z) = (self.pet.x, self.pet.z);
+        let hits: Vec<usize> = self
+            .bug_grid
+            .query(pet_x, pet_z, radius)
+            .into_iter()
+            .filter(|&i| {
+                let (dx, dz) = (self.bugs[i].x - pet_x, self.bugs[i].z - pet_z);
+                dx * dx + dz * dz < radius * radius
+            })
+            .collect();
+        let missed = hits.is_empty();
+        let count = self.hit_bugs(hits);
+        self.message = match count {
+            0 if missed => format!("{} pounced at nothing.", self.pet.name),
+            0 => "The bug's shell held. Hit it again!".to_string(),
+            _ => format!("{} squashed {} bug(s)!", self.pet.name, count),
+        };
+    }
+
+    // Eat the closest squashed bug within reach to take the edge off the pet's hunger.
+    fn eat_squashed_bug(&mut self) {
+        let combat = &self.config.combat;
+        let (pet_x, pet_z) = (self.pet.x, self.pet.z);
+        let closest = self
+            .squashed
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i, (s.x - pet_x) * (s.x - pet_x) + (s.z - pet_z) * (s.z - pet_z)))
+            .filter(|&(_, dist_sq)| dist_sq < combat.eat_radius * combat.eat_radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match closest {
+            Some((i, _)) => {
+                self.squashed.swap_remove(i);
+                let nutrition = combat.eat_hunger * self.config.species.get(&self.pet.species).ability.bug_nutrition();
+                self.pet.hunger = (self.pet.hunger - nutrition).max(0.0);
+                self.message = format!("{} ate a squashed bug. Crunchy!", self.pet.name);
+            }
+            None => self.message = "No squashed bugs nearby to eat.".to_string(),
+        }
+    }
+}
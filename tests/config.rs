@@ -0,0 +1,85 @@
+// Loading the gameplay configuration, and the errors for values that would break the game.
+use gaemthesecond::config::{Config, ConfigError};
+
+// The message of the error the given TOML is rejected with, if it is invalid.
+fn invalid(text: &str) -> String {
+    match Config::parse(text) {
+        Err(ConfigError::Invalid(message)) => message,
+        Err(e) => panic!("expected an out of range value, got: {}", e),
+        Ok(_) => panic!("expected {:?} to be rejected", text),
+    }
+}
+
+#[test]
+fn shipped_file_matches_the_defaults() {
+    let text = std::fs::read_to_string("config.toml").unwrap();
+    assert_eq!(format!("{:?}", Config::parse(&text).unwrap()), format!("{:?}", Config::default()));
+}
+
+#[test]
+fn empty_file_gives_the_defaults() {
+    assert_eq!(format!("{:?}", Config::parse("").unwrap()), format!("{:?}", Config::default()));
+}
+
+#[test]
+fn missing_values_fall_back_to_the_defaults() {
+    let config = Config::parse("[movement]\naccel = 0.05\n").unwrap();
+    assert_eq!(config.movement.accel, 0.05);
+    assert_eq!(config.movement.friction, Config::default().movement.friction);
+}
+
+#[test]
+fn missing_file_gives_the_defaults() {
+    let config = Config::load("this-config-does-not-exist.toml").unwrap();
+    assert_eq!(format!("{:?}", config), format!("{:?}", Config::default()));
+}
+
+#[test]
+fn removed_action_keys_are_still_accepted() {
+    let config = Config::parse("[actions]\nfeed_hunger = 15.0\nplay_happiness = 15.0\nplay_energy_cost = 10.0\nsleep_energy = 6.0\nsleep_hunger = 5.0\n").unwrap();
+    assert_eq!(config.actions.sleep_energy, 6.0);
+}
+
+#[test]
+fn unknown_and_mistyped_keys_are_parse_errors() {
+    assert!(matches!(Config::parse("[movement]\nacel = 0.05\n"), Err(ConfigError::Parse(_))));
+    assert!(matches!(Config::parse("[movement]\naccel = \"fast\"\n"), Err(ConfigError::Parse(_))));
+    assert!(matches!(Config::parse("[nonsense]\n"), Err(ConfigError::Parse(_))));
+}
+
+#[test]
+fn out_of_range_values_name_the_key() {
+    assert!(invalid("[bugs]\ncontact_radius = 0.0\n").contains("bugs.contact_radius"));
+    assert!(invalid("[movement]\naccel = -1.0\n").contains("movement.accel"));
+    assert!(invalid("[movement]\nfriction = 1.5\n").contains("movement.friction"));
+    assert!(invalid("[bugs]\nthreat_radius = 0.5\n").contains("bugs.threat_radius"));
+    assert!(invalid("[bugs]\ndespawn_distance = 10.0\n").contains("bugs.despawn_distance"));
+    assert!(invalid("[day]\nstart_hour = 24.0\n").contains("day.start_hour"));
+    assert!(invalid("[growth]\nadult_age = 10.0\n").contains("growth.adult_age"));
+    assert!(invalid("[mood]\ncalm_radius = 1.0\n").contains("mood.calm_radius"));
+    assert!(invalid("[items]\nmax_stack = 0\n").contains("items.max_stack"));
+    assert!(invalid("[fetch]\nmax_speed = 0.1\n").contains("fetch.max_speed"));
+}
+
+#[test]
+fn not_a_number_is_rejected() {
+    assert!(invalid("[movement]\naccel = nan\n").contains("movement.accel"));
+    assert!(invalid("[bugs]\ncontact_radius = inf\n").contains("bugs.contact_radius"));
+}
+
+#[test]
+fn species_need_unique_names_and_sane_stats() {
+    let species = |name: &str, hunger: f32| format!("[[species]]\nname = \"{}\"\nhunger = {}\n", name, hunger);
+    assert!(Config::parse(&species("slug", 10.0)).is_ok());
+    assert!(invalid(&(species("slug", 10.0) + &species("slug", 10.0))).contains("unique"));
+    assert!(invalid(&species("", 10.0)).contains("unique"));
+    assert!(invalid(&species("slug", 150.0)).contains("species.slug.hunger"));
+}
+
+#[test]
+fn spawning_bugs_need_a_kind_to_spawn() {
+    let text = "[difficulty.normal]\nspawn_count = 5\nspawn_interval = 1.0\nspawn_distance = 50.0\nstart_speed = 0.1\n\
+                speed_increase = 0.0\nspeed_increase_interval = 1.0\nmax_bugs = 10\n\
+                weights = { swarmer = 0, flanker = 0, beetle = 0, flyer = 0 }\n";
+    assert!(invalid(text).contains("difficulty.normal.weights"));
+}
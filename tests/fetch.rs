@@ -0,0 +1,63 @@
+// Walking into the ball kicks it; only the throw key picks it up for a game of fetch.
+use gaemthesecond::config::{Config, Difficulty};
+use gaemthesecond::input::InputState;
+use gaemthesecond::world::{TICK, World};
+
+// A hatched pet with the ball lying still just in front of it.
+fn world() -> World {
+    let mut world = World::new("Fetcher".to_string(), "blob", 5, Difficulty::Peaceful, Config::default());
+    let hatch = Config::default().growth.hatch_age;
+    while world.pet.survival_time(world.time) < hatch as f64 + 1.0 {
+        world.step(TICK, &InputState::default());
+    }
+    place_ball(&mut world, 1.2);
+    world
+}
+
+fn place_ball(world: &mut World, distance: f32) {
+    let pet = &world.pet;
+    world.ball.x = pet.x + pet.yaw.sin() * distance;
+    world.ball.z = pet.z + pet.yaw.cos() * distance;
+    (world.ball.vx, world.ball.vy, world.ball.vz, world.ball.height) = (0.0, 0.0, 0.0, 0.0);
+}
+
+fn hold(world: &mut World, input: InputState, ticks: usize) {
+    for _ in 0..ticks {
+        world.step(TICK, &input);
+    }
+}
+
+#[test]
+fn walking_into_the_ball_kicks_it() {
+    let mut world = world();
+    let start = (world.ball.x, world.ball.z);
+    hold(&mut world, InputState { forward: true, ..InputState::default() }, 60);
+    assert!(!world.ball.held);
+    let (dx, dz) = (world.ball.x - start.0, world.ball.z - start.1);
+    assert!(dx * dx + dz * dz > 1.0, "the ball should have been pushed away");
+}
+
+#[test]
+fn the_throw_key_picks_the_ball_up_and_throws_it() {
+    let mut world = world();
+    let throw = InputState { throw: true, ..InputState::default() };
+    hold(&mut world, throw.clone(), 30);
+    assert!(world.ball.held);
+    // Keeping the key down after picking the ball up does not wind up a throw.
+    assert_eq!(world.fetch.charge, 0.0);
+    hold(&mut world, InputState::default(), 1);
+    hold(&mut world, throw, 30);
+    assert!(world.fetch.charge > 0.0);
+    hold(&mut world, InputState::default(), 1);
+    assert!(!world.ball.held);
+    assert!(world.fetch.active());
+}
+
+#[test]
+fn the_throw_key_away_from_the_ball_does_nothing() {
+    let mut world = world();
+    place_ball(&mut world, 10.0);
+    hold(&mut world, InputState { throw: true, ..InputState::default() }, 30);
+    assert!(!world.ball.held);
+    assert!(!world.fetch.active());
+}
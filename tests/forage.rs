@@ -0,0 +1,53 @@
+// Remembering what was taken from each chunk, and letting it grow back.
+use gaemthesecond::forage::Forage;
+
+#[test]
+fn untouched_features_are_ripe() {
+    let forage = Forage::default();
+    assert!(forage.is_ripe(0, 0, 0, 0.0));
+    assert!(forage.is_ripe(-3, 7, 12, 1000.0));
+}
+
+#[test]
+fn taken_features_grow_back_at_ready_at() {
+    let mut forage = Forage::default();
+    forage.take(2, -1, 3, 50.0);
+    assert!(!forage.is_ripe(2, -1, 3, 0.0));
+    assert!(!forage.is_ripe(2, -1, 3, 49.9));
+    assert!(forage.is_ripe(2, -1, 3, 50.0));
+    // Other features, in the same chunk or at the same index in another one, are untouched.
+    assert!(forage.is_ripe(2, -1, 4, 0.0));
+    assert!(forage.is_ripe(-1, 2, 3, 0.0));
+}
+
+#[test]
+fn taking_again_replaces_the_old_time() {
+    let mut forage = Forage::default();
+    forage.take(0, 0, 1, 100.0);
+    forage.take(0, 0, 1, 20.0);
+    assert!(forage.is_ripe(0, 0, 1, 20.0));
+}
+
+#[test]
+fn regrow_only_forgets_what_has_grown_back() {
+    let mut forage = Forage::default();
+    forage.take(0, 0, 0, 10.0);
+    forage.take(0, 0, 1, 30.0);
+    forage.take(5, 5, 0, 20.0);
+    forage.regrow(20.0);
+    assert!(forage.is_ripe(0, 0, 0, 0.0));
+    assert!(forage.is_ripe(5, 5, 0, 0.0));
+    assert!(!forage.is_ripe(0, 0, 1, 25.0));
+    forage.regrow(30.0);
+    // Nothing is left growing, so nothing is left to save.
+    assert_eq!(serde_json::to_string(&forage).unwrap(), serde_json::to_string(&Forage::default()).unwrap());
+}
+
+#[test]
+fn forage_survives_a_save() {
+    let mut forage = Forage::default();
+    forage.take(-4, 9, 2, 75.0);
+    let loaded: Forage = serde_json::from_str(&serde_json::to_string(&forage).unwrap()).unwrap();
+    assert!(!loaded.is_ripe(-4, 9, 2, 74.0));
+    assert!(loaded.is_ripe(-4, 9, 2, 75.0));
+}
@@ -0,0 +1,93 @@
+// Adding items to the inventory and taking them out again.
+use gaemthesecond::inventory::{Inventory, ItemCategory, ItemKind};
+
+#[test]
+fn add_stacks_items_of_the_same_kind() {
+    let mut inventory = Inventory::default();
+    assert_eq!(inventory.add(ItemKind::Berry, 2, 10), 2);
+    assert_eq!(inventory.add(ItemKind::Apple, 1, 10), 1);
+    assert_eq!(inventory.add(ItemKind::Berry, 3, 10), 3);
+    assert_eq!(inventory.count(ItemKind::Berry), 5);
+    assert_eq!(inventory.count(ItemKind::Apple), 1);
+    assert_eq!(inventory.count(ItemKind::Stick), 0);
+    // One stack per kind, in the order they were first picked up.
+    let kinds: Vec<ItemKind> = inventory.stacks.iter().map(|stack| stack.kind).collect();
+    assert_eq!(kinds, vec![ItemKind::Berry, ItemKind::Apple]);
+}
+
+#[test]
+fn add_stops_at_the_stack_limit() {
+    let mut inventory = Inventory::default();
+    assert_eq!(inventory.add(ItemKind::Mushroom, 4, 5), 4);
+    assert_eq!(inventory.add(ItemKind::Mushroom, 4, 5), 1);
+    assert_eq!(inventory.add(ItemKind::Mushroom, 1, 5), 0);
+    assert_eq!(inventory.count(ItemKind::Mushroom), 5);
+}
+
+#[test]
+fn add_leaves_no_empty_stack_behind() {
+    let mut inventory = Inventory::default();
+    assert_eq!(inventory.add(ItemKind::Feather, 3, 0), 0);
+    assert_eq!(inventory.add(ItemKind::Feather, 0, 10), 0);
+    assert!(inventory.stacks.is_empty());
+    assert_eq!(inventory.selected(), None);
+}
+
+#[test]
+fn take_uses_up_one_item_at_a_time() {
+    let mut inventory = Inventory::default();
+    inventory.add(ItemKind::Apple, 2, 10);
+    assert!(inventory.take(ItemKind::Apple));
+    assert_eq!(inventory.count(ItemKind::Apple), 1);
+    assert!(inventory.take(ItemKind::Apple));
+    assert!(inventory.stacks.is_empty());
+    assert!(!inventory.take(ItemKind::Apple));
+    assert!(!inventory.take(ItemKind::Medicine));
+}
+
+#[test]
+fn take_keeps_the_same_item_selected() {
+    let mut inventory = Inventory::default();
+    for kind in [ItemKind::Berry, ItemKind::Stick, ItemKind::Medicine] {
+        inventory.add(kind, 1, 10);
+    }
+    inventory.select_next();
+    inventory.select_next();
+    assert_eq!(inventory.selected(), Some(ItemKind::Medicine));
+    // Emptying a stack before the selected one moves the selection along with it.
+    assert!(inventory.take(ItemKind::Berry));
+    assert_eq!(inventory.selected(), Some(ItemKind::Medicine));
+    // Emptying the selected stack at the end of the hotbar selects the one before it.
+    assert!(inventory.take(ItemKind::Medicine));
+    assert_eq!(inventory.selected(), Some(ItemKind::Stick));
+    assert!(inventory.take(ItemKind::Stick));
+    assert_eq!(inventory.selected(), None);
+}
+
+#[test]
+fn select_next_wraps_around() {
+    let mut inventory = Inventory::default();
+    inventory.select_next();
+    assert_eq!(inventory.selected(), None);
+    inventory.add(ItemKind::Berry, 1, 10);
+    inventory.add(ItemKind::Stick, 1, 10);
+    inventory.select_next();
+    assert_eq!(inventory.selected(), Some(ItemKind::Stick));
+    inventory.select_next();
+    assert_eq!(inventory.selected(), Some(ItemKind::Berry));
+}
+
+#[test]
+fn pick_prefers_the_selected_item() {
+    let mut inventory = Inventory::default();
+    for kind in [ItemKind::Berry, ItemKind::Feather, ItemKind::Apple] {
+        inventory.add(kind, 1, 10);
+    }
+    assert_eq!(inventory.pick(ItemCategory::Food), Some(ItemKind::Berry));
+    inventory.select_next();
+    inventory.select_next();
+    assert_eq!(inventory.pick(ItemCategory::Food), Some(ItemKind::Apple));
+    // Nothing of the category is selected, so the first one carried is used.
+    assert_eq!(inventory.pick(ItemCategory::Toy), Some(ItemKind::Feather));
+    assert_eq!(inventory.pick(ItemCategory::Medicine), None);
+}
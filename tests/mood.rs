@@ -0,0 +1,103 @@
+// Moods are entered at their thresholds but only left once the stats have moved the margin past them.
+use gaemthesecond::config::{Config, MoodConfig};
+use gaemthesecond::mood::Mood;
+use gaemthesecond::pet::Pet;
+
+// A pet whose stats put it in no particular mood.
+fn pet() -> Pet {
+    let config = Config::default();
+    let mut pet = Pet::new("Testy".to_string(), config.species.get("blob"));
+    pet.hunger = 30.0;
+    pet.happiness = 50.0;
+    pet.energy = 60.0;
+    pet
+}
+
+#[test]
+fn neutral_stats_are_content() {
+    let config = MoodConfig::default();
+    assert_eq!(Mood::Content.next(&pet(), None, &config), Mood::Content);
+}
+
+#[test]
+fn grumpy_needs_the_margin_to_wear_off() {
+    let config = MoodConfig::default();
+    let mut pet = pet();
+    pet.hunger = config.grumpy_hunger - 1.0;
+    assert_eq!(Mood::Content.next(&pet, None, &config), Mood::Content);
+    pet.hunger = config.grumpy_hunger + 1.0;
+    let mood = Mood::Content.next(&pet, None, &config);
+    assert_eq!(mood, Mood::Grumpy);
+    // Dropping just below the threshold does not calm the pet down yet...
+    pet.hunger = config.grumpy_hunger - 1.0;
+    assert_eq!(mood.next(&pet, None, &config), Mood::Grumpy);
+    // ...only dropping the whole margin below it does.
+    pet.hunger = config.grumpy_hunger - config.margin - 1.0;
+    assert_eq!(mood.next(&pet, None, &config), Mood::Content);
+}
+
+#[test]
+fn wobbling_stats_do_not_flicker() {
+    let config = MoodConfig::default();
+    let mut pet = pet();
+    let mut mood = Mood::Content;
+    let mut changes = 0;
+    for step in 0..100 {
+        pet.energy = config.sleepy_energy + if step % 2 == 0 { -1.0 } else { 1.0 };
+        let next = mood.next(&pet, None, &config);
+        if next != mood {
+            changes += 1;
+        }
+        mood = next;
+    }
+    assert_eq!(mood, Mood::Sleepy);
+    assert_eq!(changes, 1);
+}
+
+#[test]
+fn bugs_scare_up_close_and_calm_further_out() {
+    let config = MoodConfig::default();
+    let pet = pet();
+    let between = (config.scare_radius + config.calm_radius) / 2.0;
+    assert_eq!(Mood::Content.next(&pet, Some(between), &config), Mood::Content);
+    let mood = Mood::Content.next(&pet, Some(config.scare_radius - 1.0), &config);
+    assert_eq!(mood, Mood::Scared);
+    assert_eq!(mood.next(&pet, Some(between), &config), Mood::Scared);
+    assert_eq!(mood.next(&pet, None, &config), Mood::Content);
+}
+
+#[test]
+fn sleeping_pets_are_not_scared() {
+    let config = MoodConfig::default();
+    let mut pet = pet();
+    pet.asleep = true;
+    assert_eq!(Mood::Scared.next(&pet, Some(1.0), &config), Mood::Sleepy);
+}
+
+#[test]
+fn higher_priority_moods_win() {
+    let config = MoodConfig::default();
+    let mut pet = pet();
+    // Hungry and bored at once: grumpy comes first.
+    pet.hunger = 90.0;
+    pet.happiness = 10.0;
+    assert_eq!(Mood::Content.next(&pet, None, &config), Mood::Grumpy);
+    // A bug close by beats both.
+    assert_eq!(Mood::Grumpy.next(&pet, Some(1.0), &config), Mood::Scared);
+    pet.hunger = 30.0;
+    assert_eq!(Mood::Content.next(&pet, None, &config), Mood::Bored);
+}
+
+#[test]
+fn playful_needs_both_happiness_and_energy() {
+    let config = MoodConfig::default();
+    let mut pet = pet();
+    pet.happiness = config.playful_happiness + 5.0;
+    pet.energy = config.playful_energy - 5.0;
+    assert_eq!(Mood::Content.next(&pet, None, &config), Mood::Content);
+    pet.energy = config.playful_energy + 5.0;
+    let mood = Mood::Content.next(&pet, None, &config);
+    assert_eq!(mood, Mood::Playful);
+    pet.happiness = config.playful_happiness - config.margin / 2.0;
+    assert_eq!(mood.next(&pet, None, &config), Mood::Playful);
+}
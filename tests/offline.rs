@@ -0,0 +1,104 @@
+// Aging a continued pet for the time the game was closed, within the configured limits.
+use gaemthesecond::config::{Config, OfflineConfig, SpeciesConfig};
+use gaemthesecond::growth::LifeStage;
+use gaemthesecond::input::InputState;
+use gaemthesecond::offline::apply_offline_decay;
+use gaemthesecond::pet::Pet;
+use gaemthesecond::weather::Weather;
+
+fn species() -> SpeciesConfig {
+    Config::default().species.0[0].clone()
+}
+
+fn pet() -> Pet {
+    let mut pet = Pet::new("Away".to_string(), &species());
+    pet.hunger = 20.0;
+    pet.happiness = 80.0;
+    pet.energy = 50.0;
+    pet
+}
+
+#[test]
+fn long_absence_stops_at_the_caps() {
+    let limits = OfflineConfig::default();
+    let mut pet = pet();
+    let report = apply_offline_decay(&mut pet, 2.0 * 24.0 * 3600.0, &species(), &limits);
+    assert_eq!(pet.hunger, limits.hunger_cap);
+    assert_eq!(pet.happiness, limits.happiness_floor);
+    assert_eq!(pet.energy, 100.0);
+    assert_eq!(report.hunger, (20.0, limits.hunger_cap));
+    assert!(pet.is_alive);
+}
+
+#[test]
+fn caps_come_from_the_config() {
+    let config = Config::parse("[offline]\nhunger_cap = 50.0\nhappiness_floor = 60.0\n").unwrap();
+    let mut pet = pet();
+    apply_offline_decay(&mut pet, 3600.0, &species(), &config.offline);
+    assert_eq!(pet.hunger, 50.0);
+    assert_eq!(pet.happiness, 60.0);
+}
+
+#[test]
+fn short_absence_is_below_the_caps() {
+    let mut pet = pet();
+    apply_offline_decay(&mut pet, 10.0, &species(), &OfflineConfig::default());
+    assert!(pet.hunger > 20.0 && pet.hunger < 90.0);
+    assert!(pet.happiness < 80.0 && pet.happiness > 10.0);
+}
+
+#[test]
+fn stats_past_the_caps_are_left_alone() {
+    let mut pet = pet();
+    pet.hunger = 95.0;
+    pet.happiness = 5.0;
+    apply_offline_decay(&mut pet, 3600.0, &species(), &OfflineConfig::default());
+    assert_eq!(pet.hunger, 95.0);
+    assert_eq!(pet.happiness, 5.0);
+}
+
+#[test]
+fn backwards_clock_changes_nothing() {
+    let mut pet = pet();
+    let report = apply_offline_decay(&mut pet, -500.0, &species(), &OfflineConfig::default());
+    assert_eq!((pet.hunger, pet.happiness, pet.energy), (20.0, 80.0, 50.0));
+    assert_eq!(report.elapsed, 0.0);
+}
+
+#[test]
+fn absence_is_limited_to_max_elapsed() {
+    let limits = OfflineConfig { hunger_cap: 100.0, happiness_floor: 0.0, max_elapsed: 30.0 };
+    let (mut short, mut long) = (pet(), pet());
+    apply_offline_decay(&mut short, 30.0, &species(), &limits);
+    apply_offline_decay(&mut long, 30000.0, &species(), &limits);
+    assert_eq!((short.hunger, short.happiness), (long.hunger, long.happiness));
+}
+
+#[test]
+fn out_of_range_limits_are_rejected() {
+    assert!(Config::parse("[offline]\nmax_elapsed = -1.0\n").is_err());
+    assert!(Config::parse("[offline]\nhunger_cap = 120.0\n").is_err());
+}
+
+#[test]
+fn offline_rates_match_the_online_ones() {
+    let config = Config::default();
+    let cat = config.species.get("cat");
+    let weather = Weather::default().effects(&config);
+    assert_eq!((weather.drain, weather.happiness_loss), (1.0, 0.0));
+    let mut online = Pet::new("Kit".to_string(), cat);
+    (online.hunger, online.happiness) = (20.0, 80.0);
+    // Hatch the egg, so the baby's faster decay and the cat's own rates both count.
+    online.update(0.0, &InputState::default(), 30.0, &config, &weather);
+    assert_eq!(online.stage, LifeStage::Baby);
+    let mut offline = online.clone();
+
+    for step in 1..=60 {
+        online.update(0.5, &InputState::default(), 30.0 + step as f64 * 0.5, &config, &weather);
+    }
+    let limits = OfflineConfig { hunger_cap: 100.0, happiness_floor: 0.0, ..OfflineConfig::default() };
+    apply_offline_decay(&mut offline, 30.0, cat, &limits);
+    assert!((online.hunger - offline.hunger).abs() < 0.001, "{} vs {}", online.hunger, offline.hunger);
+    assert!((online.happiness - offline.happiness).abs() < 0.001, "{} vs {}", online.happiness, offline.happiness);
+    assert!(offline.hunger - 20.0 > 30.0, "a baby gets hungry faster than the base rate");
+}
@@ -0,0 +1,64 @@
+// Recording a run and playing it back must reproduce the run exactly.
+use std::fs;
+
+use gaemthesecond::config::{Config, Difficulty};
+use gaemthesecond::input::InputState;
+use gaemthesecond::replay::{self, Player, Recorder, Replay};
+use gaemthesecond::world::{TICK, World};
+use macroquad::math::Vec2;
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("gaemthesecond-{}-{}.json", name, std::process::id())).to_string_lossy().into_owned()
+}
+
+// Input that uses every kind of field a tick record has to keep.
+fn input(tick: usize) -> InputState {
+    InputState {
+        forward: tick % 200 < 150,
+        right: tick % 90 < 20,
+        sprint: tick % 300 < 60,
+        jump: tick.is_multiple_of(120),
+        pounce: tick % 400 == 399,
+        stick: Vec2::new(0.0, if tick % 500 > 450 { 0.5 } else { 0.0 }),
+        look_delta: Vec2::new(0.01, -0.02),
+        first_person: tick % 1000 > 800,
+        ..InputState::default()
+    }
+}
+
+#[test]
+fn playback_reproduces_the_run() {
+    // The pet hatches right away, so the recorded input drives it from the first ticks on.
+    let mut config = Config::default();
+    config.growth.hatch_age = 0.1;
+    let mut world = World::new("Replayed".to_string(), "bird", 11, Difficulty::Nightmare, config);
+    let start = (world.pet.x, world.pet.z);
+    let path = temp_path("replay");
+    let mut recorder = Recorder::new(path.clone(), &world);
+    for tick in 0..1200 {
+        let input = input(tick);
+        world.step(TICK, &input);
+        recorder.record(&input);
+    }
+    recorder.finish().unwrap();
+    let (dx, dz) = (world.pet.x - start.0, world.pet.z - start.1);
+    assert!(dx.abs() + dz.abs() > 1.0, "the recorded input should have moved the pet");
+
+    let replay = Replay::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let mut replayed = replay.start;
+    let mut player = Player::new(replay.ticks);
+    while let Some(input) = player.next_input() {
+        replayed.step(TICK, &input);
+    }
+    assert_eq!(serde_json::to_string(&replayed).unwrap(), serde_json::to_string(&world).unwrap());
+}
+
+#[test]
+fn every_run_gets_its_own_file() {
+    assert_eq!(replay::run_path("run.json", 1), "run.json");
+    assert_eq!(replay::run_path("run.json", 2), "run-2.json");
+    assert_eq!(replay::run_path("replays/v1.2/run.json", 3), "replays/v1.2/run-3.json");
+    assert_eq!(replay::run_path("replays.d/run", 2), "replays.d/run-2");
+    assert_eq!(replay::run_path(".hidden", 2), ".hidden-2");
+}
@@ -0,0 +1,100 @@
+// Saving a world and loading it again must give back the same world.
+use std::fs;
+
+use gaemthesecond::config::{Config, Difficulty};
+use gaemthesecond::growth::LifeStage;
+use gaemthesecond::input::InputState;
+use gaemthesecond::mood::Mood;
+use gaemthesecond::save::{self, SaveError};
+use gaemthesecond::world::{TICK, World};
+
+// A path in the temp directory that no other test uses.
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("gaemthesecond-{}-{}.json", name, std::process::id())).to_string_lossy().into_owned()
+}
+
+// A configuration in which the pet hatches right away, so the saved pet is moving about.
+fn hatched_config() -> Config {
+    let mut config = Config::default();
+    config.growth.hatch_age = 0.1;
+    config
+}
+
+// Walks the pet forward, makes it hungry enough to turn grumpy, and ends in a dash and a jump,
+// so the saved pet is mid-air with its velocity, dash cooldown and mood all set (the bugs
+// may scare it before it gets grumpy).
+fn run_about(world: &mut World) {
+    for _ in 0..240 {
+        world.step(TICK, &InputState { forward: true, ..InputState::default() });
+    }
+    world.pet.hunger = 90.0;
+    world.step(TICK, &InputState { forward: true, dash: true, ..InputState::default() });
+    world.step(TICK, &InputState { forward: true, jump: true, ..InputState::default() });
+    world.step(TICK, &InputState { forward: true, ..InputState::default() });
+
+    let pet = &world.pet;
+    assert!(pet.death_time.is_none() && pet.stage != LifeStage::Egg);
+    assert!(pet.vx.abs() + pet.vz.abs() > 0.0 && pet.height > 0.0 && pet.dash_cooldown > 0.0);
+    assert_ne!(pet.mood, Mood::Content);
+}
+
+#[test]
+fn saved_world_loads_back_the_same() {
+    let mut world = World::new("Saved".to_string(), "cat", 5, Difficulty::Normal, hatched_config());
+    run_about(&mut world);
+    let path = temp_path("roundtrip");
+    save::save_world(&world, &path).unwrap();
+    let (loaded, saved_at) = save::load_world(&path, &hatched_config()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), serde_json::to_string(&world).unwrap());
+    assert!(saved_at > 0);
+}
+
+#[test]
+fn loaded_world_keeps_running_the_same() {
+    let mut world = World::new("Saved".to_string(), "blob", 9, Difficulty::Nightmare, hatched_config());
+    run_about(&mut world);
+    let path = temp_path("continue");
+    save::save_world(&world, &path).unwrap();
+    let (mut loaded, _) = save::load_world(&path, &hatched_config()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let input = InputState { forward: true, left: true, ..InputState::default() };
+    for _ in 0..300 {
+        world.step(TICK, &input);
+        loaded.step(TICK, &input);
+    }
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), serde_json::to_string(&world).unwrap());
+}
+
+#[test]
+fn saves_with_an_old_config_still_load() {
+    // Older builds saved their configuration with the world, including keys that no longer exist.
+    let world = World::new("Old".to_string(), "blob", 3, Difficulty::Normal, Config::default());
+    let path = temp_path("oldconfig");
+    save::save_world(&world, &path).unwrap();
+    let mut json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    json["world"]["config"] = serde_json::json!({ "actions": { "feed_hunger": 15.0, "sleep_hunger": 5.0 } });
+    fs::write(&path, json.to_string()).unwrap();
+
+    let config = Config::parse("[actions]\nsleep_energy = 9.0\n").unwrap();
+    let (loaded, _) = save::load_world(&path, &config).unwrap();
+    fs::remove_file(&path).unwrap();
+    // The loaded world runs with the configuration it was given, not the saved one.
+    assert_eq!(loaded.config.actions.sleep_energy, 9.0);
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let path = temp_path("version");
+    fs::write(&path, r#"{"version": 999, "world": {}}"#).unwrap();
+    let result = save::load_world(&path, &Config::default());
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(SaveError::Version(999))));
+}
+
+#[test]
+fn missing_file_is_an_io_error() {
+    assert!(matches!(save::load_world(&temp_path("missing"), &Config::default()), Err(SaveError::Io(_))));
+}
@@ -0,0 +1,75 @@
+// The simulation is headless and deterministic: these tests step a World without a window
+// and check that the same start and the same input always give the same result, at any tick length.
+use gaemthesecond::config::{Config, Difficulty};
+use gaemthesecond::input::InputState;
+use gaemthesecond::world::{TICK, World};
+
+// A short scripted run: walk, turn, stop to feed and sleep, and walk on.
+fn script(tick: usize) -> InputState {
+    match tick % 600 {
+        0..=179 => InputState { forward: true, ..InputState::default() },
+        180..=239 => InputState { forward: true, right: true, ..InputState::default() },
+        240 => InputState { feed: true, ..InputState::default() },
+        300 => InputState { sleep: true, ..InputState::default() },
+        420 => InputState { sleep: true, ..InputState::default() },
+        421..=599 => InputState { back: true, left: true, ..InputState::default() },
+        _ => InputState::default(),
+    }
+}
+
+// A new world whose pet hatches right away. An egg ignores the movement keys, so only a hatched
+// pet shows whether movement is the same every time.
+fn hatched(seed: u64, difficulty: Difficulty) -> World {
+    let mut config = Config::default();
+    config.growth.hatch_age = 0.1;
+    World::new("Tester".to_string(), "blob", seed, difficulty, config)
+}
+
+fn run(seed: u64, difficulty: Difficulty, ticks: usize) -> World {
+    let mut world = hatched(seed, difficulty);
+    for tick in 0..ticks {
+        world.step(TICK, &script(tick));
+    }
+    world
+}
+
+#[test]
+fn same_seed_and_input_give_the_same_world() {
+    let first = serde_json::to_string(&run(42, Difficulty::Nightmare, 1200)).unwrap();
+    let second = serde_json::to_string(&run(42, Difficulty::Nightmare, 1200)).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn different_seeds_give_different_worlds() {
+    let first = serde_json::to_string(&run(1, Difficulty::Nightmare, 600)).unwrap();
+    let second = serde_json::to_string(&run(2, Difficulty::Nightmare, 600)).unwrap();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn smaller_steps_give_the_same_stats_and_position() {
+    // Without bugs nothing random happens, so only the tick length differs between the runs.
+    // Nothing stands in the pet's way with this seed: bumping into a tree at a slightly different
+    // spot sends the pet off in another direction, which is not what is tested here.
+    let mut whole = hatched(1, Difficulty::Peaceful);
+    let mut split = whole.clone();
+    for tick in 0..600 {
+        let input = InputState { forward: tick < 450, left: tick % 300 < 40, ..InputState::default() };
+        whole.step(TICK, &input);
+        for _ in 0..4 {
+            split.step(TICK / 4.0, &input);
+        }
+    }
+    let (a, b) = (&whole.pet, &split.pet);
+    assert!(a.x.abs() + a.z.abs() > 1.0, "the pet should have moved");
+    assert!(a.is_alive && b.is_alive);
+    let close = |x: f32, y: f32, tolerance: f32| (x - y).abs() <= tolerance;
+    assert!(close(a.hunger, b.hunger, 0.1), "hunger {} vs {}", a.hunger, b.hunger);
+    assert!(close(a.happiness, b.happiness, 0.1), "happiness {} vs {}", a.happiness, b.happiness);
+    assert!(close(a.energy, b.energy, 0.1), "energy {} vs {}", a.energy, b.energy);
+    assert!(close(a.health, b.health, 0.1), "health {} vs {}", a.health, b.health);
+    assert!(close(a.x, b.x, 0.5) && close(a.z, b.z, 0.5), "position ({}, {}) vs ({}, {})", a.x, a.z, b.x, b.z);
+    assert!(close(a.yaw, b.yaw, 0.01), "yaw {} vs {}", a.yaw, b.yaw);
+    assert!((whole.time - split.time).abs() < 1e-6);
+}
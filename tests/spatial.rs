@@ -0,0 +1,57 @@
+// The spatial grid must find every point near a position, without having to look at all of them.
+use gaemthesecond::rng::Rng;
+use gaemthesecond::spatial::{CELL_SIZE, SpatialGrid};
+
+fn scattered_points(count: usize) -> Vec<(f32, f32)> {
+    let mut rng = Rng::new(3);
+    (0..count).map(|_| (rng.range_u32(0, 20000) as f32 / 100.0 - 100.0, rng.range_u32(0, 20000) as f32 / 100.0 - 100.0)).collect()
+}
+
+#[test]
+fn query_finds_every_point_in_range() {
+    let points = scattered_points(2000);
+    let mut grid = SpatialGrid::default();
+    grid.rebuild(points.iter().copied());
+    for &(x, z, radius) in &[(0.0, 0.0, 5.0), (-37.5, 12.25, 20.0), (99.0, -99.0, 3.0), (8.0, 8.0, 0.0)] {
+        let found = grid.query(x, z, radius);
+        for (i, &(px, pz)) in points.iter().enumerate() {
+            let (dx, dz) = (px - x, pz - z);
+            if dx * dx + dz * dz <= radius * radius {
+                assert!(found.contains(&i), "point {} at ({}, {}) missed around ({}, {})", i, px, pz, x, z);
+            }
+        }
+        // Only the cells touching the square around the position are looked at.
+        let reach = radius + CELL_SIZE * 2.0;
+        assert!(found.iter().all(|&i| (points[i].0 - x).abs() <= reach && (points[i].1 - z).abs() <= reach));
+        // Every point is found once.
+        let mut unique = found.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), found.len());
+    }
+}
+
+#[test]
+fn nearby_matches_query() {
+    let points = scattered_points(500);
+    let mut grid = SpatialGrid::default();
+    grid.rebuild(points.iter().copied());
+    assert_eq!(grid.nearby(10.0, -20.0, 15.0).collect::<Vec<_>>(), grid.query(10.0, -20.0, 15.0));
+}
+
+#[test]
+fn rebuild_forgets_the_old_points() {
+    let mut grid = SpatialGrid::default();
+    grid.rebuild([(0.0, 0.0), (1.0, 1.0)].into_iter());
+    grid.rebuild([(50.0, 50.0)].into_iter());
+    assert!(grid.query(0.0, 0.0, 2.0).is_empty());
+    assert_eq!(grid.query(50.0, 50.0, 1.0), vec![0]);
+}
+
+#[test]
+fn negative_coordinates_land_in_their_own_cells() {
+    let mut grid = SpatialGrid::new(4.0);
+    grid.rebuild([(-0.5, -0.5), (0.5, 0.5)].into_iter());
+    assert_eq!(grid.query(-2.0, -2.0, 1.0), vec![0]);
+    assert_eq!(grid.query(2.0, 2.0, 1.0), vec![1]);
+}